@@ -0,0 +1,235 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, Ident, LitStr};
+
+/// Derives a parser for a struct with named fields from a format string.
+///
+/// Every `{field}` placeholder is parsed with the field's `Field` implementation, everything else must match
+/// literally. List fields name their separator, e.g. `#[aoc(format = "{result}: {operands: ' '}")]`. Braces are
+/// escaped as `{{` and `}}`.
+#[proc_macro_derive(AocParse, attributes(aoc))]
+pub fn derive_aoc_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+enum Segment {
+    Literal(String),
+    Placeholder {
+        name: String,
+        separator: Option<String>,
+    },
+}
+
+fn expand(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &input.ident;
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(name, "`AocParse` only supports structs"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            name,
+            "`AocParse` only supports structs with named fields",
+        ));
+    };
+    let format = format_attribute(&input)?;
+    let segments =
+        parse_format(&format.value()).map_err(|message| Error::new(format.span(), message))?;
+
+    let field_names: Vec<String> = fields
+        .named
+        .iter()
+        .map(|field| field.ident.as_ref().unwrap().to_string())
+        .collect();
+    let mut used = Vec::new();
+    for segment in &segments {
+        if let Segment::Placeholder { name, .. } = segment {
+            if !field_names.contains(name) {
+                return Err(Error::new(
+                    format.span(),
+                    format!("unknown field `{}` in format", name),
+                ));
+            }
+            if used.contains(name) {
+                return Err(Error::new(
+                    format.span(),
+                    format!("field `{}` appears more than once in format", name),
+                ));
+            }
+            used.push(name.clone());
+        }
+    }
+    if let Some(missing) = field_names.iter().find(|field| !used.contains(field)) {
+        return Err(Error::new(
+            format.span(),
+            format!("field `{}` is missing in format", missing),
+        ));
+    }
+
+    let steps = segments
+        .iter()
+        .enumerate()
+        .map(|(index, segment)| match segment {
+            Segment::Literal(text) => quote! {
+                let (input, _) = ::advent_of_code::format::literal(#text)(input)?;
+            },
+            Segment::Placeholder { name, separator } => {
+                let ident = Ident::new(name, Span::call_site());
+                let terminator = match segments.get(index + 1) {
+                    Some(Segment::Literal(text)) => quote! { Some(#text) },
+                    _ => quote! { None },
+                };
+                let parser = match separator {
+                    Some(separator) => {
+                        quote! { ::advent_of_code::format::list(#separator, #terminator) }
+                    }
+                    None => quote! { ::advent_of_code::format::field(#terminator) },
+                };
+                quote! {
+                    let (input, #ident) = ::advent_of_code::format::context(#name, #parser)(input)?;
+                }
+            }
+        });
+    let idents = field_names
+        .iter()
+        .map(|name| Ident::new(name, Span::call_site()));
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::advent_of_code::format::Field for #name #type_generics #where_clause {
+            fn parse_field(input: &str) -> ::advent_of_code::format::FieldResult<'_, Self> {
+                #(#steps)*
+                Ok((input, Self { #(#idents),* }))
+            }
+        }
+
+        impl #impl_generics ::std::str::FromStr for #name #type_generics #where_clause {
+            type Err = ::advent_of_code::ParseError;
+
+            fn from_str(input: &str) -> Result<Self, Self::Err> {
+                ::advent_of_code::format::parse_complete(input)
+            }
+        }
+    })
+}
+
+fn format_attribute(input: &DeriveInput) -> Result<LitStr, Error> {
+    let mut format = None;
+    for attribute in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("aoc"))
+    {
+        attribute.parse_nested_meta(|meta| {
+            if meta.path.is_ident("format") {
+                format = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `format = \"...\"`"))
+            }
+        })?;
+    }
+    format.ok_or_else(|| {
+        Error::new_spanned(&input.ident, "missing `#[aoc(format = \"...\")]` attribute")
+    })
+}
+
+fn parse_format(format: &str) -> Result<Vec<Segment>, String> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut placeholder = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => placeholder.push(c),
+                        None => {
+                            return Err(format!(
+                                "unterminated `{{` in placeholder `{{{}`",
+                                placeholder
+                            ))
+                        }
+                    }
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                } else if let Some(Segment::Placeholder { .. }) = segments.last() {
+                    return Err(format!(
+                        "placeholder `{{{}}}` must be separated from the previous one by literal text",
+                        placeholder
+                    ));
+                }
+                segments.push(parse_placeholder(&placeholder)?);
+            }
+            '}' => return Err("unmatched `}` in format, use `}}` for a literal brace".to_string()),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+    Ok(segments)
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<Segment, String> {
+    let (name, separator) = match placeholder.split_once(':') {
+        Some((name, separator)) => {
+            let separator = separator.trim();
+            let unquoted = separator
+                .strip_prefix('\'')
+                .and_then(|s| s.strip_suffix('\''))
+                .or_else(|| {
+                    separator
+                        .strip_prefix('"')
+                        .and_then(|s| s.strip_suffix('"'))
+                });
+            match unquoted {
+                Some(separator) if !separator.is_empty() => (name, Some(separator.to_string())),
+                _ => {
+                    return Err(format!(
+                        "separator of `{}` must be a non-empty quoted string",
+                        name.trim()
+                    ))
+                }
+            }
+        }
+        None => (placeholder, None),
+    };
+    let name = name.trim();
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(format!("invalid field name `{}` in format", name));
+    }
+    Ok(Segment::Placeholder {
+        name: name.to_string(),
+        separator,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unterminated_placeholder() {
+        assert_eq!(
+            parse_format("{result}: {operands").err(),
+            Some("unterminated `{` in placeholder `{operands`".to_string())
+        );
+        assert!(parse_format("{result}: {operands: ' '}").is_ok());
+        assert!(parse_format("{{literal").is_ok());
+    }
+}
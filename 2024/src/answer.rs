@@ -0,0 +1,133 @@
+//! Overflow-checked accumulation of puzzle answers.
+//!
+//! Plain `+` wraps silently in release builds, which results in a wrong but plausible answer. `Answer` widens
+//! every operation to `i128` and remembers once the value stopped fitting into `T`, so that `run_part` reports the
+//! part as failed instead of submitting it.
+
+use std::{
+    iter::{Product, Sum},
+    ops::{Add, AddAssign, Mul, MulAssign, Sub, SubAssign},
+};
+
+use crate::math::Number;
+
+/// A `T` that turns into an overflow marker as soon as an operation leaves the range of `T`, e.g.
+/// `Answer::new(u32::MAX) + 1`. Operands can be plain values or other answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Answer<T> {
+    /// `None` after an overflow.
+    value: Option<T>,
+}
+
+impl<T: Number> Answer<T> {
+    pub fn new(value: T) -> Self {
+        Self { value: Some(value) }
+    }
+
+    /// `value` converted to `T`, or an overflow if it does not fit, e.g. a `usize` count in an `Answer<u32>`.
+    pub fn convert<U: Number>(value: U) -> Self {
+        Self {
+            value: T::from_i128(value.to_i128()),
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(T::ZERO)
+    }
+
+    /// The value, or `None` if the computation overflowed.
+    pub fn value(self) -> Option<T> {
+        self.value
+    }
+
+    pub fn is_overflow(self) -> bool {
+        self.value.is_none()
+    }
+
+    fn combine(self, other: Self, operation: fn(i128, i128) -> Option<i128>) -> Self {
+        let value = match (self.value, other.value) {
+            (Some(a), Some(b)) => operation(a.to_i128(), b.to_i128()).and_then(T::from_i128),
+            _ => None,
+        };
+        Self { value }
+    }
+}
+
+impl<T: Number> Default for Answer<T> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<T: Number> From<T> for Answer<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+macro_rules! operator {
+    ($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident, $checked:ident) => {
+        impl<T: Number, R: Into<Answer<T>>> $trait<R> for Answer<T> {
+            type Output = Answer<T>;
+
+            fn $method(self, other: R) -> Answer<T> {
+                self.combine(other.into(), i128::$checked)
+            }
+        }
+
+        impl<T: Number, R: Into<Answer<T>>> $assign_trait<R> for Answer<T> {
+            fn $assign_method(&mut self, other: R) {
+                *self = self.combine(other.into(), i128::$checked);
+            }
+        }
+    };
+}
+
+operator!(Add, add, AddAssign, add_assign, checked_add);
+operator!(Sub, sub, SubAssign, sub_assign, checked_sub);
+operator!(Mul, mul, MulAssign, mul_assign, checked_mul);
+
+impl<T: Number, R: Into<Answer<T>>> Sum<R> for Answer<T> {
+    fn sum<I: Iterator<Item = R>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |sum, value| sum + value)
+    }
+}
+
+impl<T: Number, R: Into<Answer<T>>> Product<R> for Answer<T> {
+    fn product<I: Iterator<Item = R>>(iter: I) -> Self {
+        let one = T::from_i128(1).unwrap();
+        iter.fold(Self::new(one), |product, value| product * value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let mut answer = Answer::new(40u32);
+        answer += 2;
+        answer *= Answer::new(10);
+        assert_eq!((answer - 20u32).value(), Some(400));
+        assert_eq!([1u8, 2, 3].into_iter().sum::<Answer<u8>>(), Answer::new(6));
+        assert_eq!(
+            [4i64, -5].into_iter().product::<Answer<i64>>(),
+            Answer::new(-20)
+        );
+        assert_eq!(Answer::<u64>::default().value(), Some(0));
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert!((Answer::new(u32::MAX) + 1).is_overflow());
+        assert!((Answer::new(3u32) - 4).is_overflow());
+        assert!((Answer::new(u64::MAX) * u64::MAX).is_overflow());
+        assert!([200u8, 100].into_iter().sum::<Answer<u8>>().is_overflow());
+        assert!(Answer::<u16>::convert(70_000usize).is_overflow());
+        assert_eq!(Answer::<u16>::convert(7i64), Answer::new(7));
+        let mut answer = Answer::new(u16::MAX) + 1;
+        answer -= 1;
+        assert_eq!(answer.value(), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! Cellular automata on a `Matrix`, like the animated lights of 2015 day 18.
+
+use std::{collections::HashMap, hash::Hash};
+
+use crate::Matrix;
+
+/// The states of the up to eight cells around a cell. Cells on the border have fewer neighbours.
+#[derive(Clone, Copy, Debug)]
+pub struct Neighbours<T> {
+    cells: [T; 8],
+    len: usize,
+}
+
+impl<T: Copy + Eq> Neighbours<T> {
+    /// Number of neighbours in `state`.
+    pub fn count(&self, state: T) -> usize {
+        self.iter().filter(|cell| *cell == state).count()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.cells[..self.len].iter().copied()
+    }
+}
+
+/// Repetition of an automaton: the generation `start` recurs every `length` generations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Period {
+    pub start: usize,
+    pub length: usize,
+}
+
+/// Double-buffered automaton that computes every generation from the previous one with `rule`, which receives
+/// the state of a cell and of its neighbours.
+pub struct Automaton<T, F> {
+    current: Matrix<T>,
+    next: Matrix<T>,
+    rule: F,
+    /// Cells that never change, e.g. the broken corner lights.
+    stuck: Option<Matrix<bool>>,
+    generation: usize,
+}
+
+impl<T, F> Automaton<T, F>
+where
+    T: Copy + Eq + Hash,
+    F: Fn(T, &Neighbours<T>) -> T,
+{
+    pub fn new(initial: Matrix<T>, rule: F) -> Self {
+        let next = Matrix::new(initial.data.clone());
+        Self {
+            current: initial,
+            next,
+            rule,
+            stuck: None,
+            generation: 0,
+        }
+    }
+
+    /// Keeps the cells marked in `mask` at their current state forever.
+    pub fn with_stuck(mut self, mask: Matrix<bool>) -> Self {
+        assert_eq!(
+            mask.size(),
+            self.current.size(),
+            "Mask and automaton differ in size"
+        );
+        self.stuck = Some(mask);
+        self
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn matrix(&self) -> &Matrix<T> {
+        &self.current
+    }
+
+    /// Number of cells in `state`.
+    pub fn population(&self, state: T) -> usize {
+        self.current
+            .data
+            .iter()
+            .flatten()
+            .filter(|cell| **cell == state)
+            .count()
+    }
+
+    /// Advances by one generation. Returns whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let (rows, columns) = self.current.size();
+        let mut changed = false;
+        for row in 0..rows {
+            for col in 0..columns {
+                let cell = self.current.data[row][col];
+                let stuck = self.stuck.as_ref().is_some_and(|mask| mask.data[row][col]);
+                let state = if stuck {
+                    cell
+                } else {
+                    (self.rule)(cell, &self.neighbours(row, col))
+                };
+                changed |= state != cell;
+                self.next.data[row][col] = state;
+            }
+        }
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    fn neighbours(&self, row: usize, col: usize) -> Neighbours<T> {
+        let (rows, columns) = self.current.size();
+        let mut neighbours = Neighbours {
+            cells: [self.current.data[row][col]; 8],
+            len: 0,
+        };
+        for r in row.saturating_sub(1)..=(row + 1).min(rows - 1) {
+            for c in col.saturating_sub(1)..=(col + 1).min(columns - 1) {
+                if (r, c) != (row, col) {
+                    neighbours.cells[neighbours.len] = self.current.data[r][c];
+                    neighbours.len += 1;
+                }
+            }
+        }
+        neighbours
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Steps until a generation repeats one seen since the call, and stops there.
+    pub fn find_cycle(&mut self) -> Period {
+        let mut seen = HashMap::from([(self.current.data.clone(), self.generation)]);
+        loop {
+            self.step();
+            if let Some(start) = seen.insert(self.current.data.clone(), self.generation) {
+                return Period {
+                    start,
+                    length: self.generation - start,
+                };
+            }
+        }
+    }
+
+    /// Advances to the absolute `generation`, skipping whole periods once the automaton repeats itself, e.g. for
+    /// a billion generations. Returns the period if one was found on the way.
+    pub fn run_until(&mut self, generation: usize) -> Option<Period> {
+        let first = self.generation;
+        let mut history = vec![self.current.data.clone()];
+        let mut seen = HashMap::from([(self.current.data.clone(), first)]);
+        while self.generation < generation {
+            self.step();
+            if let Some(start) = seen.get(&self.current.data) {
+                let period = Period {
+                    start: *start,
+                    length: self.generation - start,
+                };
+                let offset = (generation - self.generation) % period.length;
+                self.current
+                    .data
+                    .clone_from(&history[period.start - first + offset]);
+                self.generation = generation;
+                return Some(period);
+            }
+            seen.insert(self.current.data.clone(), self.generation);
+            history.push(self.current.data.clone());
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rectangles::Rectangle;
+
+    fn lights() -> Matrix<bool> {
+        let rows = [".#.#.#", "...##.", "#....#", "..#...", "#.#..#", "####.."];
+        Matrix::new(
+            rows.iter()
+                .map(|row| row.chars().map(|c| c == '#').collect())
+                .collect(),
+        )
+    }
+
+    fn life(on: bool, neighbours: &Neighbours<bool>) -> bool {
+        matches!((on, neighbours.count(true)), (true, 2) | (_, 3))
+    }
+
+    #[test]
+    fn test_animation() {
+        let mut automaton = Automaton::new(lights(), life);
+        assert_eq!(automaton.population(true), 15);
+        automaton.run(4);
+        assert_eq!((automaton.generation(), automaton.population(true)), (4, 4));
+        automaton.run(2);
+        assert!(!automaton.step());
+        assert_eq!(automaton.population(false), 32);
+    }
+
+    #[test]
+    fn test_stuck_corners() {
+        let mut initial = lights();
+        let mut mask = Matrix::filled(6, 6, false);
+        for corner in [(0, 0), (0, 5), (5, 0), (5, 5)] {
+            *initial.get(corner).unwrap() = true;
+            *mask.get(corner).unwrap() = true;
+        }
+        let mut automaton = Automaton::new(initial, life).with_stuck(mask);
+        automaton.run(5);
+        assert_eq!(automaton.population(true), 17);
+    }
+
+    #[test]
+    fn test_cycles() {
+        let mut blinker = Matrix::filled(5, 5, '.');
+        blinker.fill_rectangle(&Rectangle::new((2, 1), (2, 3)), '#');
+        let rule = |cell, neighbours: &Neighbours<char>| match (cell, neighbours.count('#')) {
+            ('#', 2) | (_, 3) => '#',
+            _ => '.',
+        };
+        let mut automaton = Automaton::new(blinker, rule);
+        automaton.run(3);
+        assert_eq!(
+            automaton.find_cycle(),
+            Period {
+                start: 3,
+                length: 2
+            }
+        );
+        assert_eq!(automaton.generation(), 5);
+
+        let period = automaton.run_until(1_000_000_000);
+        assert_eq!(
+            period,
+            Some(Period {
+                start: 5,
+                length: 2
+            })
+        );
+        assert_eq!(automaton.generation(), 1_000_000_000);
+        assert_eq!(automaton.population('#'), 3);
+        assert_eq!(automaton.matrix().find('#'), Some((2, 1)));
+        automaton.step();
+        assert_eq!(automaton.matrix().find('#'), Some((1, 2)));
+    }
+}
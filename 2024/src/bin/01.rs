@@ -1,34 +1,34 @@
 advent_of_code::solution!(1);
 
-use advent_of_code::{parse_as_int, parse_lines};
-use itertools::{multiunzip, Itertools};
-use nom::{character::complete::space1, sequence::separated_pair};
+use advent_of_code::{
+    answer::Answer, counter::Counter, parse_int, try_parse_lines, InputError, ParseError,
+};
+use itertools::Itertools;
+use nom::{character::complete::space1, sequence::separated_pair, IResult};
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (lhs, rhs): (Vec<u32>, Vec<u32>) = multiunzip(parse_lines(input, &line_parser));
-    let result = lhs
+pub fn part_one(input: &str) -> Result<Answer<u32>, InputError> {
+    let (lhs, rhs): (Vec<u32>, Vec<u32>) = try_parse_lines(input, line_parser)?.into_iter().unzip();
+    Ok(lhs
         .iter()
         .sorted()
         .zip(rhs.into_iter().sorted())
         .map(|(l, r)| l.abs_diff(r))
-        .sum();
-    Some(result)
+        .sum())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (lhs, rhs): (Vec<u32>, Vec<u32>) = multiunzip(parse_lines(input, &line_parser));
-    let number_count = rhs.iter().counts();
-    let result = lhs.into_iter().fold(0, |acc, num| {
-        acc + num * *number_count.get(&num).unwrap_or(&0) as u32
-    });
-    Some(result)
+pub fn part_two(input: &str) -> Result<Answer<u32>, InputError> {
+    let (lhs, rhs): (Vec<u32>, Vec<u32>) = try_parse_lines(input, line_parser)?.into_iter().unzip();
+    let bound = rhs.iter().max().map_or(0, |max| *max as usize + 1);
+    let mut number_count = Counter::dense(bound);
+    number_count.extend(rhs);
+    Ok(lhs
+        .into_iter()
+        .map(|num| Answer::new(num) * Answer::convert(number_count[&num]))
+        .sum())
 }
 
-fn line_parser(line: &str) -> (u32, u32) {
-    match separated_pair(parse_as_int, space1, parse_as_int)(line) {
-        Ok(("", (lhs, rhs))) => (lhs, rhs),
-        _ => panic!("Unexpected input line '{}'", line),
-    }
+fn line_parser(line: &str) -> IResult<&str, (u32, u32), ParseError> {
+    separated_pair(parse_int(), space1, parse_int())(line)
 }
 
 #[cfg(test)]
@@ -38,12 +38,18 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(11));
+        assert_eq!(result, Ok(Answer::new(11)));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(31));
+        assert_eq!(result, Ok(Answer::new(31)));
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let error = part_one("3   4\n4 x\n").unwrap_err();
+        assert_eq!((error.line_number, error.column), (2, 3));
     }
 }
@@ -1,19 +1,19 @@
 advent_of_code::solution!(2);
 
-use advent_of_code::{parse_as_int, parse_lines};
+use advent_of_code::{answer::Answer, parse_int, try_parse_lines, InputError, ParseError};
 use itertools::Itertools;
-use nom::{character::complete::space1, multi::separated_list1};
+use nom::{character::complete::char, multi::separated_list1, IResult};
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let result = parse_lines(input, &line_parser).fold(0, |acc, report| {
-        acc + (is_safe(&report, None) == Safety::Safe) as u32
-    });
-    Some(result)
+pub fn part_one(input: &str) -> Result<Answer<u32>, InputError> {
+    Ok(try_parse_lines(input, line_parser)?
+        .iter()
+        .map(|report| (is_safe(report, None) == Safety::Safe) as u32)
+        .sum())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut number_of_safe_reports = 0;
-    for report in parse_lines(input, &line_parser) {
+pub fn part_two(input: &str) -> Result<Answer<u32>, InputError> {
+    let mut number_of_safe_reports = Answer::zero();
+    for report in try_parse_lines(input, line_parser)? {
         match is_safe(&report, None) {
             Safety::Safe => number_of_safe_reports += 1,
             Safety::Unsafe(index) => {
@@ -24,7 +24,7 @@ pub fn part_two(input: &str) -> Option<u32> {
             }
         }
     }
-    Some(number_of_safe_reports)
+    Ok(number_of_safe_reports)
 }
 
 #[derive(PartialEq)]
@@ -33,7 +33,7 @@ enum Safety {
     Unsafe(usize),
 }
 
-fn is_safe(report: &Vec<u32>, skip: Option<usize>) -> Safety {
+fn is_safe(report: &[u32], skip: Option<usize>) -> Safety {
     let filtered_reports = report
         .iter()
         .enumerate()
@@ -42,27 +42,22 @@ fn is_safe(report: &Vec<u32>, skip: Option<usize>) -> Safety {
             _ => Some(level),
         })
         .tuple_windows();
-    let mut first_unsafe_index = 0 as usize;
     let mut is_increasing = true;
     let mut is_decreasing = true;
     let mut safe_difference = true;
-    for (first, second) in filtered_reports {
+    for (first_unsafe_index, (first, second)) in filtered_reports.enumerate() {
         is_increasing &= first < second;
         is_decreasing &= first > second;
         safe_difference &= first.abs_diff(*second) <= 3;
         if !(is_increasing || is_decreasing) || !safe_difference {
             return Safety::Unsafe(first_unsafe_index);
         }
-        first_unsafe_index += 1;
     }
     Safety::Safe
 }
 
-fn line_parser(line: &str) -> Vec<u32> {
-    match separated_list1(space1, parse_as_int)(line) {
-        Ok(("", list)) => list,
-        _ => panic!("Unexpected input line '{}'", line),
-    }
+fn line_parser(line: &str) -> IResult<&str, Vec<u32>, ParseError> {
+    separated_list1(char(' '), parse_int())(line)
 }
 
 #[cfg(test)]
@@ -72,12 +67,18 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(2));
+        assert_eq!(result, Ok(Answer::new(2)));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(4));
+        assert_eq!(result, Ok(Answer::new(4)));
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let error = part_one("7 6 4 2 1\n7 6 x 2\n").unwrap_err();
+        assert_eq!((error.line_number, error.column), (2, 4));
     }
 }
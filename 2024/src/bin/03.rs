@@ -1,33 +1,56 @@
 advent_of_code::solution!(3);
 
-use std::num::ParseIntError;
+use advent_of_code::{
+    answer::Answer,
+    tokenizer::{Argument, Signature, Tokenizer},
+};
 
-use regex::Regex;
+pub fn part_one(input: &str) -> Answer<u32> {
+    sum_up_multiplications(input, true)
+}
 
-pub fn part_one(input: &str) -> Option<u32> {
-    sum_up_multiplications(input, true).ok()
+pub fn part_two(input: &str) -> Answer<u32> {
+    sum_up_multiplications(input, false)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    sum_up_multiplications(input, false).ok()
+#[derive(Clone, Copy)]
+enum Instruction {
+    Do,
+    Dont,
+    Mul,
 }
 
-fn sum_up_multiplications(line: &str, all: bool) -> Result<u32, ParseIntError> {
-    let operations_regex =
-        Regex::new(r"do\(\)|don't\(\)|mul\((?<lhs>\d{1,3}),(?<rhs>\d{1,3})\)").unwrap();
-    let mut sum = 0;
+const INSTRUCTIONS: Tokenizer<Instruction> = Tokenizer::new(&[
+    Signature::new(Instruction::Do, "do", &[]),
+    Signature::new(Instruction::Dont, "don't", &[]),
+    Signature::new(
+        Instruction::Mul,
+        "mul",
+        &[
+            Argument::Number { max_digits: 3 },
+            Argument::Number { max_digits: 3 },
+        ],
+    ),
+]);
+
+fn sum_up_multiplications(line: &str, all: bool) -> Answer<u32> {
+    let mut sum = Answer::zero();
     let mut enabled = true;
-    for capture in operations_regex.captures_iter(line) {
-        let operation = &capture[0];
-        if operation == "do()" {
-            enabled = true;
-        } else if operation == "don't()" {
-            enabled = false;
-        } else if enabled || all {
-            sum += capture["lhs"].parse::<u32>()? * capture["rhs"].parse::<u32>()?;
+    for token in INSTRUCTIONS.tokenize(line) {
+        match token.kind {
+            Instruction::Do => enabled = true,
+            Instruction::Dont => enabled = false,
+            Instruction::Mul if enabled || all => {
+                sum += token
+                    .arguments()
+                    .iter()
+                    .map(|argument| *argument as u32)
+                    .product::<Answer<u32>>();
+            }
+            Instruction::Mul => {}
         }
     }
-    Ok(sum)
+    sum
 }
 
 #[cfg(test)]
@@ -39,7 +62,7 @@ mod tests {
         let result = part_one(&advent_of_code::template::read_file_part(
             "examples", DAY, 1,
         ));
-        assert_eq!(result, Some(161));
+        assert_eq!(result, Answer::new(161));
     }
 
     #[test]
@@ -47,6 +70,6 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file_part(
             "examples", DAY, 2,
         ));
-        assert_eq!(result, Some(48));
+        assert_eq!(result, Answer::new(48));
     }
 }
@@ -1,155 +1,67 @@
 advent_of_code::solution!(4);
 
-use advent_of_code::{Coordinate, Matrix, MatrixVisitor};
-use itertools::Itertools;
+use advent_of_code::{bytes::ByteGrid, Coordinate};
+
+/// All eight directions a word can run in, as `(row, column)` steps.
+const DIRECTIONS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let mut matrix = Matrix::new(input.lines().map(Vec::from).collect::<Vec<Vec<u8>>>());
-
-    let mut xmas = Xmas::new([b'X', b'M', b'A', b'S']);
-    let mut samx = Xmas::new([b'S', b'A', b'M', b'X']);
-
-    matrix.visit_elements_row_wise(&mut xmas);
-    matrix.visit_elements_row_wise(&mut samx);
-
-    matrix.visit_elements_column_wise(&mut xmas);
-    matrix.visit_elements_column_wise(&mut samx);
-
-    matrix.visit_elements_diagonal_wise(&mut xmas);
-    matrix.visit_elements_diagonal_wise(&mut samx);
-
-    matrix.visit_elements_anti_diagonal_wise(&mut xmas);
-    matrix.visit_elements_anti_diagonal_wise(&mut samx);
-
-    Some(xmas.matches + samx.matches)
+    let grid = ByteGrid::new(input.as_bytes());
+    let matches = coordinates(&grid)
+        .filter(|coordinate| grid.get(*coordinate) == Some(b'X'))
+        .map(|coordinate| {
+            DIRECTIONS
+                .iter()
+                .filter(|direction| {
+                    (1..).zip(b"MAS").all(|(distance, letter)| {
+                        neighbour(&grid, coordinate, **direction, distance) == Some(*letter)
+                    })
+                })
+                .count() as u32
+        })
+        .sum();
+    Some(matches)
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut matrix = Matrix::new(input.lines().map(Vec::from).collect::<Vec<Vec<u8>>>());
-
-    let mut mas = Mas::new([b'M', b'A', b'S']);
-    let mut sam = Mas::new([b'S', b'A', b'M']);
-
-    matrix.visit_elements_diagonal_wise(&mut mas);
-    matrix.visit_elements_diagonal_wise(&mut sam);
-
-    matrix.visit_elements_anti_diagonal_wise(&mut mas);
-    matrix.visit_elements_anti_diagonal_wise(&mut sam);
-
-    mas.center_points.append(&mut sam.center_points);
-    let result = mas
-        .center_points
-        .into_iter()
-        .counts()
-        .into_values()
-        .filter(|count| count == &2)
+    let grid = ByteGrid::new(input.as_bytes());
+    let is_mas = |coordinate, (dr, dc)| {
+        let ends = (
+            neighbour(&grid, coordinate, (dr, dc), 1),
+            neighbour(&grid, coordinate, (-dr, -dc), 1),
+        );
+        matches!(ends, (Some(b'M'), Some(b'S')) | (Some(b'S'), Some(b'M')))
+    };
+    let matches = coordinates(&grid)
+        .filter(|coordinate| grid.get(*coordinate) == Some(b'A'))
+        .filter(|coordinate| is_mas(*coordinate, (1, 1)) && is_mas(*coordinate, (1, -1)))
         .count() as u32;
-    Some(result)
-}
-
-struct Xmas {
-    buffer: [u8; 4],
-    index: usize,
-    expectation: [u8; 4],
-    matches: u32,
-}
-
-impl Xmas {
-    fn new(expectation: [u8; 4]) -> Self {
-        Self {
-            buffer: [0; 4],
-            index: 0,
-            expectation,
-            matches: 0,
-        }
-    }
-
-    fn add(&mut self, c: u8) {
-        self.buffer[self.index] = c;
-        self.index += 1;
-    }
-
-    fn is_complete(&self) -> bool {
-        self.buffer == self.expectation
-    }
+    Some(matches)
 }
 
-impl MatrixVisitor<u8> for Xmas {
-    fn visit(&mut self, c: &mut u8, _: Coordinate) {
-        let c = *c;
-        if c == self.expectation[0] {
-            self.reset();
-            self.add(c);
-        } else if self.index == 1 && c == self.expectation[1] {
-            self.add(c);
-        } else if self.index == 2 && c == self.expectation[2] {
-            self.add(c);
-        } else if self.index == 3 && c == self.expectation[3] {
-            self.add(c);
-        } else {
-            self.reset();
-        }
-        self.matches += self.is_complete() as u32
-    }
-
-    fn reset(&mut self) {
-        self.index = 0;
-        self.buffer.fill(0);
-    }
+fn coordinates(grid: &ByteGrid) -> impl Iterator<Item = Coordinate> {
+    let (rows, columns) = grid.size();
+    (0..rows).flat_map(move |row| (0..columns).map(move |col| (row, col)))
 }
 
-struct Mas {
-    buffer: [u8; 3],
-    index: usize,
-    expectation: [u8; 3],
-    current_center: Coordinate,
-    center_points: Vec<Coordinate>,
-}
-
-impl Mas {
-    fn new(expectation: [u8; 3]) -> Self {
-        Self {
-            buffer: [0; 3],
-            index: 0,
-            expectation,
-            current_center: (0, 0),
-            center_points: Vec::new(),
-        }
-    }
-
-    fn add(&mut self, c: u8) {
-        self.buffer[self.index] = c;
-        self.index += 1;
-    }
-
-    fn is_complete(&self) -> bool {
-        self.buffer == self.expectation
-    }
-}
-
-impl MatrixVisitor<u8> for Mas {
-    fn visit(&mut self, c: &mut u8, coordinate: Coordinate) {
-        let c = *c;
-        if c == self.expectation[0] {
-            self.reset();
-            self.add(c);
-        } else if self.index == 1 && c == self.expectation[1] {
-            self.add(c);
-            self.current_center = coordinate;
-        } else if self.index == 2 && c == self.expectation[2] {
-            self.add(c);
-        } else {
-            self.reset();
-        }
-        if self.is_complete() {
-            self.center_points.push(self.current_center);
-        }
-    }
-
-    fn reset(&mut self) {
-        self.index = 0;
-        self.buffer.fill(0);
-    }
+fn neighbour(
+    grid: &ByteGrid,
+    (row, col): Coordinate,
+    (dr, dc): (isize, isize),
+    distance: isize,
+) -> Option<u8> {
+    let row = row.checked_add_signed(dr * distance)?;
+    let col = col.checked_add_signed(dc * distance)?;
+    grid.get((row, col))
 }
 
 #[cfg(test)]
@@ -1,85 +1,78 @@
-advent_of_code::solution!(5);
+advent_of_code::solution!(5, parse = parse_input, solve = solve);
 
-use std::{cmp::Ordering, num::ParseIntError};
+use advent_of_code::{answer::Answer, sections, InputError};
 
-use advent_of_code::{parse_as_int, parse_lines};
-use itertools::{Either, Itertools};
-use nom::{
-    branch::alt, bytes::complete::tag, combinator::map_res, multi::separated_list1,
-    sequence::separated_pair,
-};
-
-pub fn part_one(input: &str) -> Option<u32> {
-    solve(input).map(|result| result.0)
+pub struct Manual {
+    /// `precedes[a * pages + b]` is set by the rule `a|b`.
+    precedes: Vec<bool>,
+    /// One more than the highest page number in any rule.
+    pages: usize,
+    updates: Vec<Vec<u32>>,
 }
 
-fn solve(input: &str) -> Option<(u32, u32)> {
-    let lines = parse_lines(input, &line_parser);
-    let (relations, mut page_lines): (Vec<(u32, u32)>, Vec<Vec<u32>>) = lines
-        .filter_map(|line| line)
-        .partition_map(|input| match input {
-            Input::Relation(a, b) => Either::Left((a, b)),
-            Input::Pages(pages) => Either::Right(pages),
-        });
-    let mut ordered_pages = 0;
-    let mut corrected_pages = 0;
-    'page_loop: for pages in page_lines.iter_mut() {
-        for relation in relations.iter() {
-            if let Some(first_index) = pages.iter().position(|page| *page == relation.0) {
-                if let Some(second_index) = pages.iter().position(|page| *page == relation.1) {
-                    if first_index <= second_index {
-                        continue;
-                    }
-                    pages.sort_by(|a, b| {
-                        match relations
-                            .iter()
-                            .find(|(x, y)| *x == *a && *y == *b || *x == *b && *y == *a)
-                        {
-                            Some((left, _)) => {
-                                if *left == *a {
-                                    Ordering::Less
-                                } else {
-                                    Ordering::Greater
-                                }
-                            }
-                            None => Ordering::Equal,
-                        }
-                    });
-                    corrected_pages += pages[pages.len() / 2];
-                    continue 'page_loop;
-                }
-            }
-        }
-        ordered_pages += pages[pages.len() / 2];
+pub fn parse_input(input: &str) -> Result<Manual, InputError> {
+    sections!(input => relations: Vec<(usize, usize)> = "|", updates: Vec<Vec<u32>> = ",");
+    let pages = relations
+        .iter()
+        .map(|(a, b)| a.max(b) + 1)
+        .max()
+        .unwrap_or(0);
+    let mut precedes = vec![false; pages * pages];
+    for (a, b) in relations {
+        precedes[a * pages + b] = true;
     }
-    Some((ordered_pages, corrected_pages))
+    Ok(Manual {
+        precedes,
+        pages,
+        updates,
+    })
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    solve(input).map(|result| result.1)
+pub fn solve(manual: &Manual) -> (Answer<u32>, Answer<u32>) {
+    let mut ordered_pages = Answer::zero();
+    let mut corrected_pages = Answer::zero();
+    for pages in &manual.updates {
+        if manual.is_ordered(pages) {
+            ordered_pages += pages[pages.len() / 2];
+        } else {
+            corrected_pages += manual.middle_page(pages);
+        }
+    }
+    (ordered_pages, corrected_pages)
 }
 
-enum Input {
-    // xx|yy
-    Relation(u32, u32),
-    // xx,yy,zz
-    Pages(Vec<u32>),
-}
+impl Manual {
+    /// Whether a rule requires `a` to be printed before `b`.
+    fn precedes(&self, a: &u32, b: &u32) -> bool {
+        let (a, b) = (*a as usize, *b as usize);
+        a < self.pages && b < self.pages && self.precedes[a * self.pages + b]
+    }
 
-fn line_parser(line: &str) -> Option<Input> {
-    if line.is_empty() {
-        return None;
+    fn is_ordered(&self, pages: &[u32]) -> bool {
+        pages.iter().enumerate().all(|(index, page)| {
+            !pages[index + 1..]
+                .iter()
+                .any(|later| self.precedes(later, page))
+        })
     }
-    let relation_parser = map_res(
-        separated_pair(parse_as_int, tag("|"), parse_as_int),
-        |pair| Ok::<_, ParseIntError>(Input::Relation(pair.0, pair.1)),
-    );
-    let pages_parser = map_res(separated_list1(tag(","), parse_as_int), |list| {
-        Ok::<_, ParseIntError>(Input::Pages(list))
-    });
-    match alt((relation_parser, pages_parser))(line) {
-        Ok(("", input)) => Some(input),
-        _ => panic!("Unexpected input line '{}'", line),
+
+    /// The page that ends up in the middle once `pages` are ordered, i.e. the one with as many pages before it as
+    /// after it. Panics if the rules do not order the pages completely.
+    fn middle_page(&self, pages: &[u32]) -> u32 {
+        *pages
+            .iter()
+            .find(|page| {
+                let before = pages
+                    .iter()
+                    .filter(|other| self.precedes(other, page))
+                    .count();
+                let after = pages
+                    .iter()
+                    .filter(|other| self.precedes(page, other))
+                    .count();
+                before == pages.len() / 2 && after == pages.len() / 2
+            })
+            .unwrap_or_else(|| panic!("The rules do not determine the order of {:?}", pages))
     }
 }
 
@@ -88,14 +81,14 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(143));
+    fn test_solve() {
+        let manual = parse_input(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        assert_eq!(solve(&manual), (Answer::new(143), Answer::new(123)));
     }
 
     #[test]
-    fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(123));
+    fn test_invalid_input() {
+        assert!(parse_input("47|53\n").is_err());
+        assert!(parse_input("47|53\n1,2\n\n75,47,61\n").is_err());
     }
 }
@@ -1,18 +1,22 @@
-advent_of_code::solution!(6);
+advent_of_code::solution!(6, parse = parse_input);
 
-use advent_of_code::{parse_lines, Coordinate, Matrix, MatrixVisitor, ParseError};
+use advent_of_code::{try_parse_lines, Coordinate, InputError, Matrix, MatrixVisitor, ParseError};
 use itertools::Itertools;
 use nom::{character::complete::one_of, combinator::map_res, multi::many1, IResult};
 use std::collections::HashSet;
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut grid = Matrix::new(parse_lines(input, &line_parser).collect());
+pub fn parse_input(input: &str) -> Result<Matrix<Field>, InputError> {
+    Ok(Matrix::new(try_parse_lines(input, line_parser)?))
+}
+
+pub fn part_one(grid: &Matrix<Field>) -> Option<u32> {
+    let mut grid = grid.clone();
     let guard_position = grid.find(Field::Guard).expect("Guard not found on the map");
     run_guard(guard_position, &mut grid)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut grid = Matrix::new(parse_lines(input, &line_parser).collect());
+pub fn part_two(grid: &Matrix<Field>) -> Option<u32> {
+    let mut grid = grid.clone();
     let guard_position = grid.find(Field::Guard).expect("Guard not found on the map");
     let mut visitor = Visitor {
         guard_origin: guard_position,
@@ -127,7 +131,7 @@ enum Direction {
 }
 
 #[derive(Copy, Clone, PartialEq)]
-enum Field {
+pub enum Field {
     Free,
     Obstacle,
     Guard,
@@ -144,13 +148,8 @@ impl Field {
     }
 }
 
-fn line_parser(line: &str) -> Vec<Field> {
-    let result: IResult<&str, Vec<Field>, ParseError> =
-        many1(map_res(one_of(".#^"), Field::from_char))(line);
-    match result {
-        Ok(("", fields)) => fields,
-        _ => panic!("Unexpected input line '{}'", line),
-    }
+fn line_parser(line: &str) -> IResult<&str, Vec<Field>, ParseError> {
+    many1(map_res(one_of(".#^"), Field::from_char))(line)
 }
 
 #[cfg(test)]
@@ -159,13 +158,15 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
+        let grid = parse_input(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let result = part_one(&grid);
         assert_eq!(result, Some(41));
     }
 
     #[test]
     fn test_part_two() {
-        let result = part_two(&advent_of_code::template::read_file("examples", DAY));
+        let grid = parse_input(&advent_of_code::template::read_file("examples", DAY)).unwrap();
+        let result = part_two(&grid);
         assert_eq!(result, Some(6));
     }
 }
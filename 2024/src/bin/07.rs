@@ -1,86 +1,39 @@
 advent_of_code::solution!(7);
 
-use advent_of_code::{parse_as_big_int, parse_lines, ParseError};
-use itertools::Itertools;
-use nom::{
-    bytes::complete::tag, character::complete::space1, combinator::map_res, multi::separated_list1,
-    sequence::separated_pair,
+use advent_of_code::{
+    format::Field,
+    operators::{Operator, Solver},
+    try_parse_lines, AocParse, InputError,
 };
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let operators = [Operation::Add, Operation::Multiply];
-    let result = parse_lines(input, &line_parser)
-        .filter_map(|equation| equation.can_be_solved(&operators).then(|| equation.result))
-        .sum();
-    Some(result)
+pub fn part_one(input: &str) -> Result<u64, InputError> {
+    calibration_result(input, &[Operator::ADD, Operator::MULTIPLY])
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let operators = [Operation::Add, Operation::Multiply, Operation::Concatenate];
-    let result = parse_lines(input, &line_parser)
-        .filter_map(|equation| equation.can_be_solved(&operators).then(|| equation.result))
-        .sum();
-    Some(result)
+pub fn part_two(input: &str) -> Result<u64, InputError> {
+    let operators = [Operator::ADD, Operator::MULTIPLY, Operator::CONCATENATE];
+    calibration_result(input, &operators)
 }
 
-enum Operation {
-    Add,
-    Multiply,
-    Concatenate,
-}
-
-impl Operation {
-    fn apply(&self, a: u64, b: u64) -> u64 {
-        match self {
-            Self::Add => a + b,
-            Self::Multiply => a * b,
-            Self::Concatenate => format!("{}{}", a, b).parse().unwrap(),
-        }
-    }
+fn calibration_result(input: &str, operators: &[Operator]) -> Result<u64, InputError> {
+    let solver = Solver::new(operators);
+    Ok(try_parse_lines(input, Equation::parse_field)?
+        .into_iter()
+        .filter_map(|equation| {
+            solver
+                .is_solvable(equation.result, &equation.operands)
+                .then_some(equation.result)
+        })
+        .sum())
 }
 
+#[derive(AocParse)]
+#[aoc(format = "{result}: {operands: ' '}")]
 struct Equation {
     result: u64,
     operands: Vec<u64>,
 }
 
-impl Equation {
-    fn new(result: u64, operands: Vec<u64>) -> Self {
-        Self { result, operands }
-    }
-
-    fn can_be_solved(&self, operators: &[Operation]) -> bool {
-        let operators = (0..self.operands.len() - 1)
-            .map(|_| operators)
-            .multi_cartesian_product();
-        for combination in operators {
-            let mut result = self.operands[0];
-            for (next_operand, operation) in self.operands.iter().skip(1).zip(combination) {
-                result = operation.apply(result, *next_operand);
-            }
-            if result == self.result {
-                return true;
-            }
-        }
-        false
-    }
-}
-
-fn line_parser(line: &str) -> Equation {
-    let mut parser = map_res(
-        separated_pair(
-            parse_as_big_int,
-            tag(": "),
-            separated_list1(space1, parse_as_big_int),
-        ),
-        |(result, operands)| Ok::<_, ParseError>(Equation::new(result, operands)),
-    );
-    match parser(line) {
-        Ok(("", equation)) => equation,
-        _ => panic!("Unexpected input line '{}'", line),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,12 +41,18 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(3749));
+        assert_eq!(result, Ok(3749));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(11387));
+        assert_eq!(result, Ok(11387));
+    }
+
+    #[test]
+    fn test_invalid_line() {
+        let error = part_one("190: 10 19\n3267 81 40 27\n").unwrap_err();
+        assert_eq!((error.line_number, error.column), (2, 5));
     }
 }
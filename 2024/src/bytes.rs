@@ -0,0 +1,239 @@
+//! Byte-oriented counterparts of the `&str` helpers that work on the raw input without copying it.
+
+use crate::{scan::Integer, Coordinate};
+
+const ONES: u64 = u64::from_ne_bytes([0x01; 8]);
+const HIGH_BITS: u64 = u64::from_ne_bytes([0x80; 8]);
+
+/// Position of the first occurrence of `needle` in `haystack`. Compares eight bytes at a time.
+pub fn memchr(needle: u8, haystack: &[u8]) -> Option<usize> {
+    let pattern = ONES * needle as u64;
+    let mut chunks = haystack.chunks_exact(8);
+    let mut offset = 0;
+    for chunk in chunks.by_ref() {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap()) ^ pattern;
+        // Sets the high bit of the lowest zero byte, i.e. the first byte equal to `needle`.
+        let matches = word.wrapping_sub(ONES) & !word & HIGH_BITS;
+        if matches != 0 {
+            return Some(offset + matches.trailing_zeros() as usize / 8);
+        }
+        offset += 8;
+    }
+    chunks
+        .remainder()
+        .iter()
+        .position(|byte| *byte == needle)
+        .map(|position| offset + position)
+}
+
+/// Iterator over the lines of `input` without their `\n` or `\r\n` terminators.
+pub fn lines(input: &[u8]) -> Lines<'_> {
+    Lines { remaining: input }
+}
+
+pub struct Lines<'a> {
+    remaining: &'a [u8],
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let (line, rest) = match memchr(b'\n', self.remaining) {
+            Some(end) => (&self.remaining[..end], &self.remaining[end + 1..]),
+            None => (self.remaining, &self.remaining[self.remaining.len()..]),
+        };
+        self.remaining = rest;
+        Some(line.strip_suffix(b"\r").unwrap_or(line))
+    }
+}
+
+/// Reads the integer at the start of `input`, preceded by a `-` for signed types.
+///
+/// Returns the number together with the remaining input, or `None` if there is no number or it overflows `T`.
+pub fn number<T: Integer>(input: &[u8]) -> Option<(T, &[u8])> {
+    let negative = T::SIGNED && input.first() == Some(&b'-');
+    let digits = &input[negative as usize..];
+    let length = digits
+        .iter()
+        .position(|byte| !byte.is_ascii_digit())
+        .unwrap_or(digits.len());
+    if length == 0 {
+        return None;
+    }
+    let value = digits[..length].iter().try_fold(T::ZERO, |value, digit| {
+        value.push_digit(digit - b'0', negative)
+    })?;
+    Some((value, &digits[length..]))
+}
+
+/// Iterator over all integers in `line`, the byte counterpart of `ints` and `ints_signed` depending on `T`.
+///
+/// Panics on numbers that overflow `T`.
+pub fn numbers<T: Integer>(line: &[u8]) -> impl Iterator<Item = T> + '_ {
+    let mut remaining = line;
+    std::iter::from_fn(move || {
+        let start = remaining.iter().position(u8::is_ascii_digit)?;
+        let start = if T::SIGNED && start > 0 && remaining[start - 1] == b'-' {
+            start - 1
+        } else {
+            start
+        };
+        let (value, rest) = number(&remaining[start..]).unwrap_or_else(|| {
+            panic!(
+                "Unable to read integer at '{}'",
+                String::from_utf8_lossy(&remaining[start..])
+            )
+        });
+        remaining = rest;
+        Some(value)
+    })
+}
+
+/// Rectangular grid of bytes that borrows the input, with rows addressed through their fixed line length.
+pub struct ByteGrid<'a> {
+    data: &'a [u8],
+    rows: usize,
+    columns: usize,
+    stride: usize,
+}
+
+impl<'a> ByteGrid<'a> {
+    pub fn new(input: &'a [u8]) -> Self {
+        let columns = memchr(b'\n', input).unwrap_or(input.len());
+        let (columns, terminator) = match input[..columns].last() {
+            Some(b'\r') => (columns - 1, 2),
+            _ => (columns, 1),
+        };
+        let stride = columns + terminator;
+        let end = input.len()
+            - input
+                .iter()
+                .rev()
+                .take_while(|byte| matches!(byte, b'\r' | b'\n'))
+                .count();
+        let mut rows = 0;
+        let mut start = 0;
+        while start < end {
+            let line_end = memchr(b'\n', &input[start..end]).map_or(end, |length| start + length);
+            let raw = &input[start..line_end];
+            let line = raw.strip_suffix(b"\r").unwrap_or(raw);
+            assert!(
+                line.len() == columns && (line_end == end || raw.len() + 1 == stride),
+                "All rows need to have the same length, but row {} differs",
+                rows
+            );
+            rows += 1;
+            start = line_end + 1;
+        }
+        assert!(rows > 0);
+        Self {
+            data: input,
+            rows,
+            columns,
+            stride,
+        }
+    }
+
+    pub fn size(&self) -> Coordinate {
+        (self.rows, self.columns)
+    }
+
+    pub fn get(&self, coordinate: Coordinate) -> Option<u8> {
+        let (row, col) = coordinate;
+        if row < self.rows && col < self.columns {
+            Some(self.data[row * self.stride + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, row: usize) -> &'a [u8] {
+        let start = row * self.stride;
+        &self.data[start..start + self.columns]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.rows).map(|row| self.row(row))
+    }
+
+    pub fn find(&self, element: u8) -> Option<Coordinate> {
+        let position = memchr(element, self.data)?;
+        let coordinate = (position / self.stride, position % self.stride);
+        (coordinate.1 < self.columns).then_some(coordinate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memchr() {
+        let haystack = b"0123456789abcdefghij";
+        for (index, byte) in haystack.iter().enumerate() {
+            assert_eq!(memchr(*byte, haystack), Some(index));
+        }
+        assert_eq!(memchr(b'x', haystack), None);
+        assert_eq!(
+            memchr(0x80, &[0x01, 0x00, 0x7f, 0x81, 0x80, 0, 0, 0, 0x80]),
+            Some(4)
+        );
+        assert_eq!(memchr(b'a', b""), None);
+    }
+
+    #[test]
+    fn test_lines() {
+        let input = b"3   4\r\n4   3\n\n9   3\n";
+        assert_eq!(
+            lines(input).collect::<Vec<_>>(),
+            [&b"3   4"[..], b"4   3", b"", b"9   3"]
+        );
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(number::<u32>(b"123|45"), Some((123, &b"|45"[..])));
+        assert_eq!(number::<i8>(b"-128"), Some((-128, &b""[..])));
+        assert_eq!(number::<i8>(b"128"), None);
+        assert_eq!(number::<u32>(b"-1"), None);
+        assert_eq!(
+            numbers::<i64>(b"x=-2, y=15: 3-7").collect::<Vec<_>>(),
+            [-2, 15, 3, -7]
+        );
+        assert_eq!(numbers::<u64>(b"3   4").collect::<Vec<_>>(), [3, 4]);
+    }
+
+    #[test]
+    fn test_byte_grid() {
+        let grid = ByteGrid::new(b"..#\r\n#^.\r\n");
+        assert_eq!(grid.size(), (2, 3));
+        assert_eq!(grid.get((1, 1)), Some(b'^'));
+        assert_eq!(grid.get((1, 3)), None);
+        assert_eq!(grid.row(1), b"#^.");
+        assert_eq!(grid.find(b'^'), Some((1, 1)));
+        assert_eq!(grid.find(b'\n'), None);
+        assert_eq!(
+            ByteGrid::new(b"ab\ncd").rows().collect::<Vec<_>>(),
+            [b"ab", b"cd"]
+        );
+        let spaces = ByteGrid::new(b"#. \n.. \n");
+        assert_eq!(spaces.size(), (2, 3));
+        assert_eq!(spaces.row(1), b".. ");
+    }
+
+    #[test]
+    #[should_panic(expected = "row 1 differs")]
+    fn test_ragged_byte_grid() {
+        ByteGrid::new(b"abc\nd\nefghi");
+    }
+
+    #[test]
+    #[should_panic(expected = "row 1 differs")]
+    fn test_mixed_terminators() {
+        ByteGrid::new(b"abc\r\ndef\nghi\r\n");
+    }
+}
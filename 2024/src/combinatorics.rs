@@ -0,0 +1,310 @@
+//! Pruned searches over subsets, partitions, permutations and compositions.
+
+use std::ops::ControlFlow;
+
+/// Calls `visit` with the indices of every subset of `values` with at most `max_len` elements that sums up to
+/// `target`, e.g. the container combinations that hold exactly 150 liters. The indices are ascending.
+///
+/// Values are tried from largest to smallest, so branches are cut as soon as a value exceeds the remaining sum or
+/// the remaining values cannot reach it anymore. `visit` can stop the search by returning `ControlFlow::Break`.
+pub fn subset_sums<F>(values: &[u64], target: u64, max_len: usize, mut visit: F)
+where
+    F: FnMut(&[usize]) -> ControlFlow<()>,
+{
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|index| std::cmp::Reverse(values[*index]));
+    let sorted: Vec<u64> = order.iter().map(|index| values[*index]).collect();
+    let mut suffix_sums = vec![0; sorted.len() + 1];
+    for index in (0..sorted.len()).rev() {
+        suffix_sums[index] = suffix_sums[index + 1] + sorted[index];
+    }
+    let search = SubsetSearch {
+        order: &order,
+        sorted: &sorted,
+        suffix_sums: &suffix_sums,
+        max_len,
+    };
+    let _ = search.visit(0, target, &mut Vec::new(), &mut Vec::new(), &mut visit);
+}
+
+/// All subsets of `values` that sum up to `target` as ascending indices, see `subset_sums`.
+pub fn subsets_with_sum(values: &[u64], target: u64) -> Vec<Vec<usize>> {
+    let mut subsets = Vec::new();
+    subset_sums(values, target, values.len(), |subset| {
+        subsets.push(subset.to_vec());
+        ControlFlow::Continue(())
+    });
+    subsets
+}
+
+struct SubsetSearch<'a> {
+    /// Original indices of the values in descending order of the values.
+    order: &'a [usize],
+    sorted: &'a [u64],
+    suffix_sums: &'a [u64],
+    max_len: usize,
+}
+
+impl SubsetSearch<'_> {
+    fn visit<F>(
+        &self,
+        position: usize,
+        remaining: u64,
+        chosen: &mut Vec<usize>,
+        buffer: &mut Vec<usize>,
+        visit: &mut F,
+    ) -> ControlFlow<()>
+    where
+        F: FnMut(&[usize]) -> ControlFlow<()>,
+    {
+        if remaining == 0 {
+            buffer.clone_from(chosen);
+            buffer.sort_unstable();
+            visit(buffer)?;
+        }
+        if chosen.len() == self.max_len || self.suffix_sums[position] < remaining {
+            return ControlFlow::Continue(());
+        }
+        for next in position..self.sorted.len() {
+            if self.suffix_sums[next] < remaining {
+                break;
+            }
+            if self.sorted[next] > remaining {
+                continue;
+            }
+            chosen.push(self.order[next]);
+            let result = self.visit(
+                next + 1,
+                remaining - self.sorted[next],
+                chosen,
+                buffer,
+                visit,
+            );
+            chosen.pop();
+            result?;
+        }
+        ControlFlow::Continue(())
+    }
+}
+
+/// Splits `values` into `k` groups with equal sums. Returns the group of every value, or `None` if impossible.
+///
+/// Places the largest values first and never tries two groups with the same sum for the same value, which cuts
+/// off the symmetric branches.
+pub fn k_partition(values: &[u64], k: usize) -> Option<Vec<usize>> {
+    let total: u64 = values.iter().sum();
+    if k == 0 || !total.is_multiple_of(k as u64) {
+        return (k == 0 && values.is_empty()).then(Vec::new);
+    }
+    let target = total / k as u64;
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by_key(|index| std::cmp::Reverse(values[*index]));
+    if order.first().is_some_and(|index| values[*index] > target) {
+        return None;
+    }
+    let mut sums = vec![0; k];
+    let mut groups = vec![0; values.len()];
+    place(values, &order, target, &mut sums, &mut groups).then_some(groups)
+}
+
+fn place(
+    values: &[u64],
+    order: &[usize],
+    target: u64,
+    sums: &mut [u64],
+    groups: &mut [usize],
+) -> bool {
+    let Some((index, rest)) = order.split_first() else {
+        return true;
+    };
+    let value = values[*index];
+    for group in 0..sums.len() {
+        if sums[group] + value > target || sums[..group].contains(&sums[group]) {
+            continue;
+        }
+        sums[group] += value;
+        groups[*index] = group;
+        if place(values, rest, target, sums, groups) {
+            return true;
+        }
+        sums[group] -= value;
+    }
+    false
+}
+
+/// Rearranges `elements` into the next permutation in lexicographic order. Returns `false` and restores the first
+/// permutation, i.e. the sorted order, after the last one.
+pub fn next_permutation<T: Ord>(elements: &mut [T]) -> bool {
+    let Some(pivot) = elements.windows(2).rposition(|pair| pair[0] < pair[1]) else {
+        elements.reverse();
+        return false;
+    };
+    let successor = elements
+        .iter()
+        .rposition(|element| *element > elements[pivot])
+        .unwrap();
+    elements.swap(pivot, successor);
+    elements[pivot + 1..].reverse();
+    true
+}
+
+/// Iterator over all ways to write `total` as an ordered sum of `parts` numbers, e.g. splitting 100 teaspoons
+/// among 4 ingredients, in lexicographic order.
+#[derive(Clone, Debug)]
+pub struct Compositions {
+    total: u32,
+    min: u32,
+    max: u32,
+    current: Option<Vec<u32>>,
+}
+
+impl Compositions {
+    pub fn new(total: u32, parts: usize) -> Self {
+        Self::bounded(total, parts, 0, total)
+    }
+
+    /// Compositions whose parts all lie within `min..=max`.
+    pub fn bounded(total: u32, parts: usize, min: u32, max: u32) -> Self {
+        let mut compositions = Self {
+            total,
+            min,
+            max,
+            current: None,
+        };
+        let mut first = vec![0; parts];
+        if compositions.fill(&mut first, 0, total) {
+            compositions.current = Some(first);
+        }
+        compositions
+    }
+
+    /// Fills `parts[start..]` with the smallest sequence in lexicographic order that sums up to `sum`.
+    fn fill(&self, parts: &mut [u32], start: usize, sum: u32) -> bool {
+        let count = (parts.len() - start) as u64;
+        if count * (self.min as u64) > sum as u64 || count * (self.max as u64) < sum as u64 {
+            return false;
+        }
+        let mut sum = sum;
+        for index in (start..parts.len()).rev() {
+            let before = (index - start) as u32 * self.min;
+            parts[index] = (sum - before).min(self.max);
+            sum -= parts[index];
+        }
+        true
+    }
+}
+
+impl Iterator for Compositions {
+    type Item = Vec<u32>;
+
+    fn next(&mut self) -> Option<Vec<u32>> {
+        let mut current = self.current.take()?;
+        let result = current.clone();
+        // Sum of the parts before `index` in the loop below.
+        let mut prefix = self.total - current.last().copied().unwrap_or(0);
+        // Increment the rightmost part that leaves a valid remainder for the parts after it.
+        for index in (0..current.len().saturating_sub(1)).rev() {
+            prefix -= current[index];
+            if current[index] < self.max && prefix + current[index] < self.total {
+                let part = current[index] + 1;
+                if self.fill(&mut current, index + 1, self.total - prefix - part) {
+                    current[index] = part;
+                    self.current = Some(current);
+                    break;
+                }
+            }
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subset_sums() {
+        let containers = [20, 15, 10, 5, 5];
+        let mut combinations = subsets_with_sum(&containers, 25);
+        combinations.sort();
+        assert_eq!(
+            combinations,
+            [vec![0, 3], vec![0, 4], vec![1, 2], vec![1, 3, 4]]
+        );
+
+        let mut smallest = 0;
+        subset_sums(&containers, 25, 2, |_| {
+            smallest += 1;
+            ControlFlow::Continue(())
+        });
+        assert_eq!(smallest, 3);
+
+        let mut first = None;
+        subset_sums(&containers, 25, usize::MAX, |subset| {
+            first = Some(subset.to_vec());
+            ControlFlow::Break(())
+        });
+        assert_eq!(first, Some(vec![0, 3]));
+
+        assert_eq!(subsets_with_sum(&[3, 0], 3), [vec![0], vec![0, 1]]);
+        assert_eq!(subsets_with_sum(&[], 0), [Vec::<usize>::new()]);
+        assert!(subsets_with_sum(&containers, 100).is_empty());
+    }
+
+    #[test]
+    fn test_k_partition() {
+        let packages = [1, 2, 3, 4, 5, 7, 8, 9, 10, 11];
+        for k in [3, 4] {
+            let groups = k_partition(&packages, k).unwrap();
+            let mut sums = vec![0; k];
+            for (value, group) in packages.iter().zip(groups) {
+                sums[group] += value;
+            }
+            assert!(sums.iter().all(|sum| *sum == 60 / k as u64));
+        }
+        assert_eq!(k_partition(&packages, 7), None);
+        assert_eq!(k_partition(&[5, 5, 2], 2), None);
+        assert_eq!(k_partition(&[13, 1], 2), None);
+        assert_eq!(k_partition(&[4, 4], 1), Some(vec![0, 0]));
+    }
+
+    #[test]
+    fn test_next_permutation() {
+        let mut elements = [1, 2, 2];
+        let mut permutations = vec![elements.to_vec()];
+        while next_permutation(&mut elements) {
+            permutations.push(elements.to_vec());
+        }
+        assert_eq!(permutations, [[1, 2, 2], [2, 1, 2], [2, 2, 1]]);
+        assert_eq!(elements, [1, 2, 2]);
+
+        let mut count = 1;
+        let mut elements = ['a', 'b', 'c', 'd', 'e'];
+        while next_permutation(&mut elements) {
+            count += 1;
+        }
+        assert_eq!(count, 120);
+        assert!(!next_permutation::<u8>(&mut []));
+    }
+
+    #[test]
+    fn test_compositions() {
+        assert_eq!(
+            Compositions::new(2, 2).collect::<Vec<_>>(),
+            [vec![0, 2], vec![1, 1], vec![2, 0]]
+        );
+        assert_eq!(Compositions::new(100, 4).count(), 176_851);
+        assert!(Compositions::new(100, 4).all(|parts| parts.iter().sum::<u32>() == 100));
+        assert_eq!(
+            Compositions::bounded(5, 3, 1, 2).collect::<Vec<_>>(),
+            [vec![1, 2, 2], vec![2, 1, 2], vec![2, 2, 1]]
+        );
+        assert_eq!(Compositions::bounded(7, 3, 1, 2).count(), 0);
+        assert_eq!(Compositions::new(3, 1).collect::<Vec<_>>(), [vec![3]]);
+        assert_eq!(
+            Compositions::new(0, 0).collect::<Vec<_>>(),
+            [Vec::<u32>::new()]
+        );
+        assert_eq!(Compositions::new(1, 0).count(), 0);
+    }
+}
@@ -0,0 +1,293 @@
+//! Frequency counting over arbitrary keys, with an array backend for small integer domains.
+
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    ops::{Add, AddAssign, Index, Sub, SubAssign},
+};
+
+/// Keys that map to array indices, so that they can be counted without hashing.
+pub trait DenseKey: Sized {
+    fn to_index(&self) -> Option<usize>;
+    fn from_index(index: usize) -> Self;
+}
+
+macro_rules! dense_key {
+    ($($t:ty),*) => {
+        $(
+            impl DenseKey for $t {
+                fn to_index(&self) -> Option<usize> {
+                    (*self).try_into().ok()
+                }
+
+                fn from_index(index: usize) -> Self {
+                    index as $t
+                }
+            }
+        )*
+    };
+}
+
+dense_key!(u8, u16, u32, u64, usize);
+
+impl DenseKey for char {
+    fn to_index(&self) -> Option<usize> {
+        Some(*self as usize)
+    }
+
+    fn from_index(index: usize) -> Self {
+        char::from_u32(index as u32).unwrap()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Dense<T> {
+    counts: Vec<usize>,
+    index: fn(&T) -> Option<usize>,
+    key: fn(usize) -> T,
+}
+
+/// Multiset of keys. Indexing with a key that was never added yields 0.
+#[derive(Clone, Debug)]
+pub struct Counter<T> {
+    dense: Option<Dense<T>>,
+    /// All keys without a slot in `dense`. Never holds a zero count.
+    sparse: HashMap<T, usize>,
+}
+
+impl<T: Clone + Eq + Hash> Default for Counter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Eq + Hash> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(keys: I) -> Self {
+        let mut counter = Self::new();
+        counter.extend(keys);
+        counter
+    }
+}
+
+impl<T: Clone + Eq + Hash> Extend<T> for Counter<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, keys: I) {
+        for key in keys {
+            self.insert(key);
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Counter<T> {
+    /// Counter backed by a hash map.
+    pub fn new() -> Self {
+        Self {
+            dense: None,
+            sparse: HashMap::new(),
+        }
+    }
+
+    /// Counter backed by an array for the keys with an index below `bound`, e.g. 256 for bytes. Other keys fall
+    /// back to a hash map.
+    pub fn dense(bound: usize) -> Self
+    where
+        T: DenseKey,
+    {
+        Self {
+            dense: Some(Dense {
+                counts: vec![0; bound],
+                index: T::to_index,
+                key: T::from_index,
+            }),
+            sparse: HashMap::new(),
+        }
+    }
+
+    fn slot(&mut self, key: &T) -> Option<&mut usize> {
+        let dense = self.dense.as_mut()?;
+        let index = (dense.index)(key)?;
+        dense.counts.get_mut(index)
+    }
+
+    pub fn insert(&mut self, key: T) {
+        self.insert_n(key, 1);
+    }
+
+    pub fn insert_n(&mut self, key: T, count: usize) {
+        if count == 0 {
+            return;
+        }
+        match self.slot(&key) {
+            Some(slot) => *slot += count,
+            None => *self.sparse.entry(key).or_insert(0) += count,
+        }
+    }
+
+    pub fn remove(&mut self, key: &T) {
+        self.remove_n(key, 1);
+    }
+
+    /// Decreases the count of `key` by up to `count`, stopping at zero.
+    pub fn remove_n(&mut self, key: &T, count: usize) {
+        if let Some(slot) = self.slot(key) {
+            *slot = slot.saturating_sub(count);
+        } else if let Some(current) = self.sparse.get_mut(key) {
+            *current = current.saturating_sub(count);
+            if *current == 0 {
+                self.sparse.remove(key);
+            }
+        }
+    }
+
+    pub fn get(&self, key: &T) -> usize {
+        self[key]
+    }
+
+    /// Number of distinct keys with a positive count.
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sum of all counts.
+    pub fn total(&self) -> usize {
+        self.iter().map(|(_, count)| count).sum()
+    }
+
+    /// All keys with a positive count. Array-backed keys come first in ascending order, the others in arbitrary
+    /// order.
+    pub fn iter(&self) -> impl Iterator<Item = (T, usize)> + '_ {
+        self.dense
+            .iter()
+            .flat_map(|dense| {
+                dense
+                    .counts
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, count)| **count > 0)
+                    .map(|(index, count)| ((dense.key)(index), *count))
+            })
+            .chain(self.sparse.iter().map(|(key, count)| (key.clone(), *count)))
+    }
+
+    /// The `n` keys with the highest counts, ties broken by the smaller key.
+    pub fn most_common(&self, n: usize) -> Vec<(T, usize)>
+    where
+        T: Ord,
+    {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        entries.truncate(n);
+        entries
+    }
+}
+
+impl<T: Clone + Eq + Hash> Index<&T> for Counter<T> {
+    type Output = usize;
+
+    fn index(&self, key: &T) -> &usize {
+        let slot = self.dense.as_ref().and_then(|dense| {
+            let index = (dense.index)(key)?;
+            dense.counts.get(index)
+        });
+        slot.or_else(|| self.sparse.get(key)).unwrap_or(&0)
+    }
+}
+
+impl<T: Clone + Eq + Hash> AddAssign<&Counter<T>> for Counter<T> {
+    fn add_assign(&mut self, other: &Counter<T>) {
+        for (key, count) in other.iter() {
+            self.insert_n(key, count);
+        }
+    }
+}
+
+/// Subtraction stops at zero for every key, like removing the other multiset.
+impl<T: Clone + Eq + Hash> SubAssign<&Counter<T>> for Counter<T> {
+    fn sub_assign(&mut self, other: &Counter<T>) {
+        for (key, count) in other.iter() {
+            self.remove_n(&key, count);
+        }
+    }
+}
+
+impl<T: Clone + Eq + Hash> Add<&Counter<T>> for Counter<T> {
+    type Output = Counter<T>;
+
+    fn add(mut self, other: &Counter<T>) -> Counter<T> {
+        self += other;
+        self
+    }
+}
+
+impl<T: Clone + Eq + Hash> Sub<&Counter<T>> for Counter<T> {
+    type Output = Counter<T>;
+
+    fn sub(mut self, other: &Counter<T>) -> Counter<T> {
+        self -= other;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_counting() {
+        let mut letters: Counter<char> = "mississippi".chars().collect();
+        assert_eq!((letters[&'s'], letters[&'m'], letters[&'x']), (4, 1, 0));
+        assert_eq!((letters.len(), letters.total()), (4, 11));
+        assert_eq!(letters.most_common(2), [('i', 4), ('s', 4)]);
+        letters.remove_n(&'m', 5);
+        assert_eq!((letters.get(&'m'), letters.len()), (0, 3));
+
+        let mut bytes = Counter::dense(256);
+        bytes.extend(b"mississippi".iter().copied());
+        assert_eq!(bytes[&b's'], 4);
+        assert_eq!(
+            bytes.iter().collect::<Vec<_>>(),
+            [(b'i', 4), (b'm', 1), (b'p', 2), (b's', 4)]
+        );
+        assert_eq!(bytes.most_common(1), [(b'i', 4)]);
+    }
+
+    #[test]
+    fn test_dense_overflow() {
+        let mut numbers: Counter<u32> = Counter::dense(10);
+        numbers.extend([3, 4, 2, 1, 3, 3, 12, 12]);
+        assert_eq!(
+            (numbers[&3], numbers[&12], numbers[&9], numbers[&100]),
+            (3, 2, 0, 0)
+        );
+        assert_eq!(numbers.len(), 5);
+        numbers.remove(&12);
+        numbers.remove_n(&100, 1);
+        assert_eq!(numbers.most_common(3), [(3, 3), (1, 1), (2, 1)]);
+        assert_eq!(numbers.total(), 7);
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let a: Counter<&str> = ["x", "x", "y"].into_iter().collect();
+        let mut b = Counter::new();
+        b.insert_n("x", 3);
+        b.insert("z");
+        let sum = a.clone() + &b;
+        assert_eq!((sum[&"x"], sum[&"y"], sum[&"z"]), (5, 1, 1));
+        let difference = a.clone() - &b;
+        assert_eq!(
+            (difference[&"x"], difference[&"y"], difference.len()),
+            (0, 1, 1)
+        );
+
+        let mut dense: Counter<u8> = Counter::dense(4);
+        dense.extend([1, 1, 7]);
+        dense += &[1u8, 7, 7].into_iter().collect();
+        assert_eq!((dense[&1], dense[&7]), (3, 3));
+        dense -= &[7u8; 5].into_iter().collect();
+        assert_eq!(dense.iter().collect::<Vec<_>>(), [(1, 3)]);
+    }
+}
@@ -0,0 +1,129 @@
+//! Arithmetic on the decimal digits of non-negative integers, avoiding round trips through strings.
+
+use crate::math::Number;
+
+/// The value widened to `i128`, where every digit operation fits.
+fn wide<T: Number>(value: T) -> i128 {
+    let wide = value.to_i128();
+    assert!(wide >= 0, "{:?} is negative", value);
+    wide
+}
+
+/// Number of decimal digits, where 0 has one digit.
+pub fn digit_count<T: Number>(value: T) -> u32 {
+    match wide(value) {
+        0 => 1,
+        value => value.ilog10() + 1,
+    }
+}
+
+/// `10^digit_count(value)`, the factor that shifts a number left to make room for `value`.
+fn shift<T: Number>(value: T) -> i128 {
+    10i128.pow(digit_count(value))
+}
+
+/// The number whose digits are those of `a` followed by those of `b`, e.g. `concat(12, 345) == 12345`, or `None`
+/// on overflow.
+pub fn checked_concat<T: Number>(a: T, b: T) -> Option<T> {
+    wide(a)
+        .checked_mul(shift(b))?
+        .checked_add(wide(b))
+        .and_then(T::from_i128)
+}
+
+/// Like `checked_concat`, but panics on overflow.
+pub fn concat<T: Number>(a: T, b: T) -> T {
+    checked_concat(a, b).expect("concatenation overflows")
+}
+
+/// Inverse of `concat`: the number left of the trailing digits `suffix`, e.g. `strip_suffix_digits(12345, 45) ==
+/// Some(123)`. Returns `None` if `value` does not end with `suffix`, and `Some(0)` if both are equal.
+pub fn strip_suffix_digits<T: Number>(value: T, suffix: T) -> Option<T> {
+    let shift = shift(suffix);
+    let (value, suffix) = (wide(value), wide(suffix));
+    if value <= suffix {
+        return (value == suffix).then_some(T::ZERO);
+    }
+    (value % shift == suffix).then(|| T::from_i128(value / shift).unwrap())
+}
+
+/// Digits from the most significant one, e.g. `[1, 2, 3]` for 123.
+pub fn digits<T: Number>(value: T) -> impl Iterator<Item = u8> {
+    let mut divisor = 10i128.pow(digit_count(value) - 1);
+    let mut remaining = Some(wide(value));
+    std::iter::from_fn(move || {
+        let value = remaining?;
+        let digit = value / divisor;
+        if divisor == 1 {
+            remaining = None;
+        } else {
+            remaining = Some(value % divisor);
+            divisor /= 10;
+        }
+        Some(digit as u8)
+    })
+}
+
+/// Digits from the least significant one, e.g. `[3, 2, 1]` for 123.
+pub fn digits_rev<T: Number>(value: T) -> impl Iterator<Item = u8> {
+    let mut remaining = Some(wide(value));
+    std::iter::from_fn(move || {
+        let value = remaining?;
+        let rest = value / 10;
+        remaining = (rest != 0).then_some(rest);
+        Some((value % 10) as u8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digit_count() {
+        assert_eq!(digit_count(0u32), 1);
+        assert_eq!(digit_count(9u8), 1);
+        assert_eq!(digit_count(10u64), 2);
+        assert_eq!(digit_count(u64::MAX), 20);
+    }
+
+    #[test]
+    fn test_concat() {
+        assert_eq!(concat(12u64, 345), 12345);
+        assert_eq!(concat(15u32, 0), 150);
+        assert_eq!(concat(0u32, 7), 7);
+        assert_eq!(checked_concat(25u8, 5), Some(255));
+        assert_eq!(checked_concat(25u8, 6), None);
+        assert_eq!(checked_concat(1u8, 100), None);
+    }
+
+    #[test]
+    fn test_strip_suffix_digits() {
+        assert_eq!(strip_suffix_digits(12345u64, 45), Some(123));
+        assert_eq!(strip_suffix_digits(12345u64, 12345), Some(0));
+        assert_eq!(strip_suffix_digits(12345u64, 44), None);
+        assert_eq!(strip_suffix_digits(150u32, 0), Some(15));
+        assert_eq!(strip_suffix_digits(5u32, 15), None);
+        assert_eq!(strip_suffix_digits(255u8, 200), None);
+        assert_eq!(strip_suffix_digits(200u8, 200), Some(0));
+        for (a, b) in [(81u64, 40), (6, 8), (486, 6), (1, 10)] {
+            assert_eq!(strip_suffix_digits(concat(a, b), b), Some(a));
+        }
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(digits(1203u32).collect::<Vec<_>>(), [1, 2, 0, 3]);
+        assert_eq!(digits(0u8).collect::<Vec<_>>(), [0]);
+        assert_eq!(digits(u64::MAX).count(), 20);
+        assert_eq!(digits_rev(1203u32).collect::<Vec<_>>(), [3, 0, 2, 1]);
+        assert_eq!(digits_rev(0u8).collect::<Vec<_>>(), [0]);
+        assert_eq!(digits(407i32).collect::<Vec<_>>(), [4, 0, 7]);
+    }
+
+    #[test]
+    #[should_panic(expected = "-3 is negative")]
+    fn test_negative() {
+        digit_count(-3i64);
+    }
+}
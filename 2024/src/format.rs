@@ -0,0 +1,160 @@
+//! Runtime support for parsers generated by `#[derive(AocParse)]`.
+
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_until},
+    character::complete::anychar,
+    combinator::{all_consuming, rest},
+    error::ErrorKind,
+    IResult, Parser,
+};
+
+use crate::{parse_int, ParseError};
+
+pub use nom::error::context;
+
+pub type FieldResult<'a, T> = IResult<&'a str, T, ParseError>;
+
+/// Types that can fill a placeholder in an `#[aoc(format = "...")]` string.
+///
+/// A placeholder only sees the text up to the literal that follows it, so implementations may consume everything.
+pub trait Field: Sized {
+    fn parse_field(input: &str) -> FieldResult<'_, Self>;
+}
+
+macro_rules! integer_field {
+    ($($t:ty),*) => {
+        $(
+            impl Field for $t {
+                fn parse_field(input: &str) -> FieldResult<'_, Self> {
+                    parse_int().parse(input)
+                }
+            }
+        )*
+    };
+}
+
+integer_field!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl Field for char {
+    fn parse_field(input: &str) -> FieldResult<'_, Self> {
+        anychar(input)
+    }
+}
+
+impl Field for String {
+    fn parse_field(input: &str) -> FieldResult<'_, Self> {
+        Ok(("", input.to_string()))
+    }
+}
+
+pub fn literal<'a>(text: &'static str) -> impl FnMut(&'a str) -> FieldResult<'a, &'a str> {
+    tag(text)
+}
+
+/// Parses the text in front of `terminator`, or all remaining text if there is none, completely as a `T`.
+pub fn field<'a, T: Field>(
+    terminator: Option<&'static str>,
+) -> impl FnMut(&'a str) -> FieldResult<'a, T> {
+    move |input| region(terminator, T::parse_field)(input)
+}
+
+/// Parses the text in front of `terminator` as a non-empty list of `T`s delimited by `separator`.
+pub fn list<'a, T: Field>(
+    separator: &'static str,
+    terminator: Option<&'static str>,
+) -> impl FnMut(&'a str) -> FieldResult<'a, Vec<T>> {
+    move |input| {
+        region(terminator, |mut input| {
+            let mut items = Vec::new();
+            loop {
+                let (rest, item) = field(Some(separator))(input)?;
+                items.push(item);
+                match tag::<_, _, ParseError>(separator)(rest) {
+                    Ok((rest, _)) => input = rest,
+                    Err(_) => return Ok((rest, items)),
+                }
+            }
+        })(input)
+    }
+}
+
+/// Runs `parser` on the text in front of `terminator` and requires it to consume that text completely.
+fn region<'a, T>(
+    terminator: Option<&'static str>,
+    mut parser: impl FnMut(&'a str) -> FieldResult<'a, T>,
+) -> impl FnMut(&'a str) -> FieldResult<'a, T> {
+    move |input| {
+        let (rest, text) = match terminator {
+            Some(terminator) => alt((take_until(terminator), rest))(input)?,
+            None => rest(input)?,
+        };
+        // Errors only know the length of the region behind them, which lacks the text after the terminator.
+        all_consuming(&mut parser)(text)
+            .map(|(_, value)| (rest, value))
+            .map_err(|error| error.map(|error| error.shift(rest.len())))
+    }
+}
+
+/// Parses the complete `input` as a `T`, as used by the generated `FromStr` implementations.
+pub fn parse_complete<T: Field>(input: &str) -> Result<T, ParseError> {
+    match all_consuming(T::parse_field)(input) {
+        Ok((_, value)) => Ok(value),
+        Err(nom::Err::Error(error) | nom::Err::Failure(error)) => Err(error),
+        Err(nom::Err::Incomplete(_)) => Err(nom::error::ParseError::from_error_kind(
+            input,
+            ErrorKind::Complete,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::AocParse;
+
+    #[derive(AocParse, Debug, PartialEq)]
+    #[aoc(format = "{result}: {operands: ' '}")]
+    struct Equation {
+        result: u64,
+        operands: Vec<u64>,
+    }
+
+    #[derive(AocParse, Debug, PartialEq)]
+    #[aoc(format = "{{{name}}} at x={x}, y={y} -> {tags: \", \"}")]
+    struct Labelled {
+        name: String,
+        x: i32,
+        y: i32,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn test_derive() {
+        assert_eq!(
+            "190: 10 19".parse(),
+            Ok(Equation {
+                result: 190,
+                operands: vec![10, 19]
+            })
+        );
+        assert_eq!(
+            "{sensor 1} at x=-2, y=15 -> a, b c".parse(),
+            Ok(Labelled {
+                name: "sensor 1".to_string(),
+                x: -2,
+                y: 15,
+                tags: vec!["a".to_string(), "b c".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn test_derive_errors() {
+        let input = "190: 10 x9";
+        let error = input.parse::<Equation>().unwrap_err();
+        assert_eq!(error.offset(input), 8);
+        assert_eq!(error.to_string(), "expected a digit while parsing operands");
+        assert!("190 10 19".parse::<Equation>().is_err());
+        assert!("190: ".parse::<Equation>().is_err());
+    }
+}
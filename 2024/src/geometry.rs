@@ -0,0 +1,190 @@
+use crate::{math::gcd, ParseError, Point};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn from_char(c: char) -> Result<Self, ParseError> {
+        match c {
+            'U' | '^' | 'N' => Ok(Direction::Up),
+            'D' | 'v' | 'S' => Ok(Direction::Down),
+            'L' | '<' | 'W' => Ok(Direction::Left),
+            'R' | '>' | 'E' => Ok(Direction::Right),
+            _ => Err(ParseError::new(format!("unknown direction '{}'", c))),
+        }
+    }
+
+    /// The `(row, column)` delta of a single step into this direction.
+    pub fn offset(&self) -> Point {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+            Direction::Right => (0, 1),
+        }
+    }
+
+    pub fn step(&self, point: Point, length: isize) -> Point {
+        let (dr, dc) = self.offset();
+        (point.0 + dr * length, point.1 + dc * length)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Location {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Follows the moves from `start` and collects every corner visited, including `start` itself.
+/// A closed boundary ends where it started, in which case the duplicate last vertex is dropped.
+pub fn vertices_from_moves<I>(start: Point, moves: I) -> Vec<Point>
+where
+    I: IntoIterator<Item = (Direction, isize)>,
+{
+    let mut vertices = vec![start];
+    let mut current = start;
+    for (direction, length) in moves {
+        current = direction.step(current, length);
+        vertices.push(current);
+    }
+    if vertices.len() > 1 && vertices.last() == vertices.first() {
+        vertices.pop();
+    }
+    vertices
+}
+
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// Twice the enclosed area computed with the shoelace formula. Always an integer for lattice polygons.
+pub fn double_area(vertices: &[Point]) -> usize {
+    edges(vertices)
+        .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+        .sum::<isize>()
+        .unsigned_abs()
+}
+
+pub fn area(vertices: &[Point]) -> f64 {
+    double_area(vertices) as f64 / 2.0
+}
+
+/// Number of lattice points on the boundary, which is the plain length for axis-aligned edges.
+pub fn perimeter(vertices: &[Point]) -> usize {
+    edges(vertices)
+        .map(|(a, b)| gcd(a.0.abs_diff(b.0), a.1.abs_diff(b.1)))
+        .sum()
+}
+
+/// Number of lattice points strictly inside the polygon according to Pick's theorem. Degenerate polygons without
+/// an area, e.g. fewer than three vertices, have none.
+pub fn interior_points(vertices: &[Point]) -> usize {
+    if vertices.len() < 3 {
+        return 0;
+    }
+    let twice_interior = double_area(vertices) as isize + 2 - perimeter(vertices) as isize;
+    (twice_interior / 2).max(0) as usize
+}
+
+/// All lattice points covered by the polygon, i.e. its interior and its boundary.
+pub fn covered_points(vertices: &[Point]) -> usize {
+    interior_points(vertices) + perimeter(vertices)
+}
+
+pub fn locate(vertices: &[Point], point: Point) -> Location {
+    let (r, c) = point;
+    let mut inside = false;
+    for (a, b) in edges(vertices) {
+        let cross = (b.0 - a.0) * (c - a.1) - (b.1 - a.1) * (r - a.0);
+        if cross == 0
+            && a.0.min(b.0) <= r
+            && r <= a.0.max(b.0)
+            && a.1.min(b.1) <= c
+            && c <= a.1.max(b.1)
+        {
+            return Location::Boundary;
+        }
+        if (a.0 > r) != (b.0 > r) {
+            // Column where the edge crosses the horizontal ray through the point, compared without division.
+            let lhs = (c - a.1) * (b.0 - a.0);
+            let rhs = (b.1 - a.1) * (r - a.0);
+            if (b.0 > a.0 && lhs < rhs) || (b.0 < a.0 && lhs > rhs) {
+                inside = !inside;
+            }
+        }
+    }
+    if inside {
+        Location::Inside
+    } else {
+        Location::Outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Vec<Point> {
+        vertices_from_moves(
+            (0, 0),
+            [
+                (Direction::Right, 4),
+                (Direction::Down, 4),
+                (Direction::Left, 4),
+                (Direction::Up, 4),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_square() {
+        let square = square();
+        assert_eq!(square, vec![(0, 0), (0, 4), (4, 4), (4, 0)]);
+        assert_eq!(area(&square), 16.0);
+        assert_eq!(perimeter(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert_eq!(covered_points(&square), 25);
+    }
+
+    #[test]
+    fn test_locate() {
+        let square = square();
+        assert_eq!(locate(&square, (2, 2)), Location::Inside);
+        assert_eq!(locate(&square, (0, 2)), Location::Boundary);
+        assert_eq!(locate(&square, (4, 4)), Location::Boundary);
+        assert_eq!(locate(&square, (5, 2)), Location::Outside);
+        assert_eq!(locate(&square, (2, -1)), Location::Outside);
+    }
+
+    #[test]
+    fn test_triangle() {
+        let triangle = [(0, 0), (0, 3), (3, 0)];
+        assert_eq!(double_area(&triangle), 9);
+        assert_eq!(perimeter(&triangle), 9);
+        assert_eq!(interior_points(&triangle), 1);
+        assert_eq!(locate(&triangle, (1, 1)), Location::Inside);
+        assert_eq!(locate(&triangle, (2, 1)), Location::Boundary);
+        assert_eq!(locate(&triangle, (2, 2)), Location::Outside);
+    }
+
+    #[test]
+    fn test_degenerate() {
+        assert_eq!(interior_points(&[]), 0);
+        assert_eq!(interior_points(&[(0, 0), (0, 3)]), 0);
+        assert_eq!(interior_points(&[(0, 0), (0, 3), (0, 5)]), 0);
+        assert_eq!(
+            Direction::from_char('x'),
+            Err(ParseError::new("unknown direction 'x'".to_string()))
+        );
+    }
+}
@@ -0,0 +1,348 @@
+//! Directed graphs over arbitrary node values, e.g. ordering rules given as `47|53`.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    hash::Hash,
+    str::FromStr,
+};
+
+use crate::InputError;
+
+/// Directed graph that stores its nodes in insertion order, which also makes all traversals deterministic.
+#[derive(Clone, Debug)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    indices: HashMap<N, usize>,
+    successors: Vec<Vec<usize>>,
+    edges: HashSet<(usize, usize)>,
+}
+
+/// Error of `Graph::topological_sort` with one of the cycles that prevent an order, starting and ending at the
+/// same node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cycle<N>(pub Vec<N>);
+
+impl<N: Clone + Eq + Hash> Default for Graph<N> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            indices: HashMap::new(),
+            successors: Vec::new(),
+            edges: HashSet::new(),
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> FromIterator<(N, N)> for Graph<N> {
+    fn from_iter<I: IntoIterator<Item = (N, N)>>(edges: I) -> Self {
+        let mut graph = Self::default();
+        for (from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads one edge per line with `separator` between its nodes, e.g. `47|53`.
+    pub fn parse(input: &str, separator: &str) -> Result<Self, InputError>
+    where
+        N: FromStr,
+    {
+        let mut graph = Self::new();
+        for (index, line) in input.lines().enumerate() {
+            let error = |offset: usize, message: &str| {
+                InputError::new(index + 1, line, offset, message.to_string())
+            };
+            let (from, to) = line
+                .split_once(separator)
+                .ok_or_else(|| error(0, "Expected an edge"))?;
+            let from = from.parse().map_err(|_| error(0, "Invalid node"))?;
+            let to = to
+                .parse()
+                .map_err(|_| error(line.len() - to.len(), "Invalid node"))?;
+            graph.add_edge(from, to);
+        }
+        Ok(graph)
+    }
+
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(index) = self.indices.get(&node) {
+            return *index;
+        }
+        self.nodes.push(node.clone());
+        self.successors.push(Vec::new());
+        self.indices.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N) {
+        let from = self.add_node(from);
+        let to = self.add_node(to);
+        if self.edges.insert((from, to)) {
+            self.successors[from].push(to);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.indices.contains_key(node)
+    }
+
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.indices
+            .get(node)
+            .into_iter()
+            .flat_map(|index| self.successors[*index].iter())
+            .map(|index| &self.nodes[*index])
+    }
+
+    /// Whether there is an edge `from -> to`, i.e. `from` must precede `to`. Runs in constant time.
+    pub fn has_edge(&self, from: &N, to: &N) -> bool {
+        match (self.indices.get(from), self.indices.get(to)) {
+            (Some(from), Some(to)) => self.edges.contains(&(*from, *to)),
+            _ => false,
+        }
+    }
+
+    /// The graph restricted to `nodes` and the edges between them. Unknown nodes are added without edges.
+    pub fn induced_subgraph<'a>(&self, nodes: impl IntoIterator<Item = &'a N>) -> Self
+    where
+        N: 'a,
+    {
+        let mut subgraph = Self::new();
+        let mut selected = Vec::new();
+        for node in nodes {
+            subgraph.add_node(node.clone());
+            if let Some(index) = self.indices.get(node) {
+                selected.push(*index);
+            }
+        }
+        let selected_set: HashSet<usize> = selected.iter().copied().collect();
+        for from in selected {
+            for to in &self.successors[from] {
+                if selected_set.contains(to) {
+                    subgraph.add_edge(self.nodes[from].clone(), self.nodes[*to].clone());
+                }
+            }
+        }
+        subgraph
+    }
+
+    /// All nodes reachable from `start` in breadth-first order, including `start` itself.
+    pub fn reachable_from(&self, start: &N) -> Vec<N> {
+        let Some(start) = self.indices.get(start) else {
+            return Vec::new();
+        };
+        let mut visited = vec![false; self.nodes.len()];
+        let mut queue = VecDeque::from([*start]);
+        let mut reachable = Vec::new();
+        visited[*start] = true;
+        while let Some(node) = queue.pop_front() {
+            reachable.push(self.nodes[node].clone());
+            for next in &self.successors[node] {
+                if !visited[*next] {
+                    visited[*next] = true;
+                    queue.push_back(*next);
+                }
+            }
+        }
+        reachable
+    }
+
+    pub fn is_reachable(&self, from: &N, to: &N) -> bool {
+        self.reachable_from(from).contains(to)
+    }
+
+    /// Orders all nodes such that every edge points forward using Kahn's algorithm. Ties are broken by insertion
+    /// order.
+    pub fn topological_sort(&self) -> Result<Vec<N>, Cycle<N>> {
+        let mut in_degrees = vec![0; self.nodes.len()];
+        for successors in &self.successors {
+            for next in successors {
+                in_degrees[*next] += 1;
+            }
+        }
+        let mut queue: VecDeque<usize> = (0..self.nodes.len())
+            .filter(|node| in_degrees[*node] == 0)
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(node) = queue.pop_front() {
+            order.push(node);
+            for next in &self.successors[node] {
+                in_degrees[*next] -= 1;
+                if in_degrees[*next] == 0 {
+                    queue.push_back(*next);
+                }
+            }
+        }
+        if order.len() == self.nodes.len() {
+            Ok(order
+                .into_iter()
+                .map(|node| self.nodes[node].clone())
+                .collect())
+        } else {
+            Err(self.find_cycle(&in_degrees))
+        }
+    }
+
+    /// Every node left with a positive in-degree after Kahn's algorithm has a predecessor that is left as well,
+    /// so walking backwards eventually runs into a cycle.
+    fn find_cycle(&self, in_degrees: &[usize]) -> Cycle<N> {
+        let mut predecessors = vec![None; self.nodes.len()];
+        for (from, successors) in self.successors.iter().enumerate() {
+            for to in successors {
+                if in_degrees[from] > 0 && in_degrees[*to] > 0 {
+                    predecessors[*to] = Some(from);
+                }
+            }
+        }
+        let mut node = in_degrees.iter().position(|degree| *degree > 0).unwrap();
+        let mut position = HashMap::new();
+        let mut path = Vec::new();
+        while !position.contains_key(&node) {
+            position.insert(node, path.len());
+            path.push(node);
+            node = predecessors[node].unwrap();
+        }
+        let mut cycle: Vec<usize> = path[position[&node]..].iter().rev().copied().collect();
+        // Start with the earliest inserted node to keep the report stable.
+        let first = (0..cycle.len()).min_by_key(|index| cycle[*index]).unwrap();
+        cycle.rotate_left(first);
+        cycle.push(cycle[0]);
+        let cycle = cycle
+            .into_iter()
+            .map(|node| self.nodes[node].clone())
+            .collect();
+        Cycle(cycle)
+    }
+
+    /// Strongly connected components using Tarjan's algorithm, in reverse topological order of the condensed
+    /// graph.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<N>> {
+        const UNVISITED: usize = usize::MAX;
+        let count = self.nodes.len();
+        let mut index = vec![UNVISITED; count];
+        let mut low_link = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = Vec::new();
+        let mut next_index = 0;
+        let mut components = Vec::new();
+        for root in 0..count {
+            if index[root] != UNVISITED {
+                continue;
+            }
+            // Explicit call stack of nodes and the position of their next successor to avoid deep recursion.
+            let mut calls = vec![(root, 0)];
+            index[root] = next_index;
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            while let Some((node, position)) = calls.last_mut() {
+                let node = *node;
+                if let Some(next) = self.successors[node].get(*position).copied() {
+                    *position += 1;
+                    if index[next] == UNVISITED {
+                        index[next] = next_index;
+                        low_link[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        calls.push((next, 0));
+                    } else if on_stack[next] {
+                        low_link[node] = low_link[node].min(index[next]);
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some((parent, _)) = calls.last() {
+                    low_link[*parent] = low_link[*parent].min(low_link[node]);
+                }
+                if low_link[node] == index[node] {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        component.push(self.nodes[member].clone());
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_lookup() {
+        let graph: Graph<u32> = Graph::parse("47|53\n97|13\n97|47", "|").unwrap();
+        assert_eq!(graph.nodes(), [47, 53, 97, 13]);
+        assert!(graph.has_edge(&97, &47));
+        assert!(!graph.has_edge(&47, &97));
+        assert!(!graph.has_edge(&1, &47));
+        assert_eq!(graph.successors(&97).collect::<Vec<_>>(), [&13, &47]);
+
+        let error = Graph::<u32>::parse("47|53\n97|x", "|").unwrap_err();
+        assert_eq!((error.line_number, error.column), (2, 4));
+    }
+
+    #[test]
+    fn test_topological_sort() {
+        let graph: Graph<u32> = [(75, 47), (47, 61), (75, 61), (61, 53), (97, 75)]
+            .into_iter()
+            .collect();
+        assert_eq!(graph.topological_sort(), Ok(vec![97, 75, 47, 61, 53]));
+
+        let graph: Graph<char> = [('a', 'b'), ('b', 'c'), ('c', 'd'), ('d', 'b'), ('x', 'a')]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            graph.topological_sort(),
+            Err(Cycle(vec!['b', 'c', 'd', 'b']))
+        );
+    }
+
+    #[test]
+    fn test_subgraph_and_reachability() {
+        let graph: Graph<u32> = [(1, 2), (2, 3), (3, 1), (3, 4)].into_iter().collect();
+        assert!(graph.topological_sort().is_err());
+        let subgraph = graph.induced_subgraph(&[4, 3, 2]);
+        assert_eq!(subgraph.topological_sort(), Ok(vec![2, 3, 4]));
+        assert_eq!(graph.reachable_from(&2), [2, 3, 1, 4]);
+        assert!(graph.is_reachable(&1, &4));
+        assert!(!graph.is_reachable(&4, &1));
+    }
+
+    #[test]
+    fn test_strongly_connected_components() {
+        let graph: Graph<u32> = [(1, 2), (2, 3), (3, 1), (3, 4), (4, 5), (5, 4), (6, 6)]
+            .into_iter()
+            .collect();
+        let mut components = graph.strongly_connected_components();
+        components.iter_mut().for_each(|component| component.sort());
+        assert_eq!(components, [vec![4, 5], vec![1, 2, 3], vec![6]]);
+    }
+}
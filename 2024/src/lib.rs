@@ -1,8 +1,43 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    marker::PhantomData,
+    num::{IntErrorKind, ParseIntError},
+    str::FromStr,
+};
 
-use nom::{character::complete::digit1, combinator::map_res, error::FromExternalError, IResult};
+use nom::{
+    character::complete::{digit1, one_of},
+    combinator::{map_res, opt, recognize},
+    error::{ErrorKind, FromExternalError},
+    sequence::pair,
+    IResult, Parser,
+};
 
+extern crate self as advent_of_code;
+
+pub mod answer;
+pub mod automaton;
+pub mod bytes;
+pub mod combinatorics;
+pub mod counter;
+pub mod digits;
+pub mod format;
+pub mod geometry;
+pub mod graph;
+pub mod math;
+pub mod md5;
+pub mod memo;
+pub mod operators;
+pub mod ranges;
+pub mod rectangles;
+pub mod scan;
+pub mod sections;
 pub mod template;
+pub mod tokenizer;
+pub mod union_find;
+pub mod vm;
+
+pub use advent_of_code_derive::AocParse;
 
 pub fn parse_as_int(input: &str) -> IResult<&str, u32> {
     map_res(digit1, |s: &str| s.parse::<u32>())(input)
@@ -12,6 +47,39 @@ pub fn parse_as_big_int(input: &str) -> IResult<&str, u64> {
     map_res(digit1, |i: &str| i.parse::<u64>())(input)
 }
 
+/// Parser for an integer of any primitive type with an optional leading `+` or `-`.
+///
+/// Values that do not fit into `T` fail with `ErrorKind::TooLarge`, a minus sign in front of an unsigned type
+/// with `ErrorKind::Digit`. The underlying `ParseIntError` is handed to the error type in both cases.
+pub fn parse_int<T>() -> IntParser<T>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    IntParser(PhantomData)
+}
+
+pub struct IntParser<T>(PhantomData<T>);
+
+impl<'a, T, E> Parser<&'a str, T, E> for IntParser<T>
+where
+    T: FromStr<Err = ParseIntError>,
+    E: nom::error::ParseError<&'a str> + FromExternalError<&'a str, ParseIntError>,
+{
+    fn parse(&mut self, input: &'a str) -> IResult<&'a str, T, E> {
+        let (rest, number) = recognize(pair(opt(one_of("+-")), digit1))(input)?;
+        match number.parse() {
+            Ok(value) => Ok((rest, value)),
+            Err(error) => {
+                let kind = match error.kind() {
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => ErrorKind::TooLarge,
+                    _ => ErrorKind::Digit,
+                };
+                Err(nom::Err::Error(E::from_external_error(input, kind, error)))
+            }
+        }
+    }
+}
+
 pub fn parse_lines<'a, LineParser, Result>(
     input: &'a str,
     line_parser: &'a LineParser,
@@ -22,51 +90,274 @@ where
     input.lines().map(line_parser)
 }
 
+/// Runs `line_parser` on every line and requires it to consume the line completely.
+///
+/// The first line that cannot be parsed is reported with its 1-based line number and column.
+pub fn try_parse_lines<'a, LineParser, Output, Error>(
+    input: &'a str,
+    mut line_parser: LineParser,
+) -> Result<Vec<Output>, InputError>
+where
+    LineParser: Parser<&'a str, Output, Error>,
+    Error: LineError,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let error = |remaining_len: usize, message: String| {
+                InputError::new(index + 1, line, line.len() - remaining_len, message)
+            };
+            match line_parser.parse(line) {
+                Ok(("", output)) => Ok(output),
+                Ok((rest, _)) => Err(error(rest.len(), "Unexpected trailing input".to_string())),
+                Err(nom::Err::Error(e) | nom::Err::Failure(e)) => {
+                    Err(error(e.remaining_len(), e.description()))
+                }
+                Err(nom::Err::Incomplete(_)) => Err(error(0, "Incomplete input".to_string())),
+            }
+        })
+        .collect()
+}
+
+/// Errors that know where in their input they occurred.
+pub trait LineError {
+    /// Length of the input that was left to parse at the point of failure.
+    fn remaining_len(&self) -> usize;
+    fn description(&self) -> String;
+}
+
+impl LineError for nom::error::Error<&str> {
+    fn remaining_len(&self) -> usize {
+        self.input.len()
+    }
+
+    fn description(&self) -> String {
+        self.code.description().to_string()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct InputError {
+    pub line_number: usize,
+    pub column: usize,
+    pub line: String,
+    pub message: String,
+}
+
+impl InputError {
+    pub(crate) fn new(line_number: usize, line: &str, byte_offset: usize, message: String) -> Self {
+        Self {
+            line_number,
+            column: line[..byte_offset].chars().count() + 1,
+            line: line.to_string(),
+            message,
+        }
+    }
+}
+
+impl Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gutter = " ".repeat(self.line_number.to_string().len());
+        writeln!(
+            f,
+            "Invalid input in line {}, column {}: {}",
+            self.line_number, self.column, self.message
+        )?;
+        writeln!(f, "{} | {}", self.line_number, self.line)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(self.column - 1))
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// What a parser expected at the position where it failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expectation {
+    Kind(ErrorKind),
+    Char(char),
+    Message(String),
+}
+
+impl Display for Expectation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Expectation::Kind(kind) => {
+                let description = match kind {
+                    ErrorKind::Digit => "a digit",
+                    ErrorKind::Alpha => "a letter",
+                    ErrorKind::AlphaNumeric => "a letter or digit",
+                    ErrorKind::Space | ErrorKind::MultiSpace => "whitespace",
+                    ErrorKind::CrLf => "a line break",
+                    ErrorKind::Eof => "end of input",
+                    ErrorKind::TooLarge => "a number in range",
+                    kind => kind.description(),
+                };
+                write!(f, "expected {}", description)
+            }
+            Expectation::Char(c) => write!(f, "expected '{}'", c),
+            Expectation::Message(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Error type for `nom` parsers that keeps track of where parsing failed and which contexts were active.
+///
+/// Positions are stored as the length of the input that was left, so they can be resolved against the original
+/// input later on without tying the error to its lifetime.
+#[derive(Clone, Debug, PartialEq)]
 pub struct ParseError {
-    message: String,
+    remaining_len: usize,
+    expected: Expectation,
+    contexts: Vec<(usize, &'static str)>,
 }
 
 impl ParseError {
     pub fn new(message: String) -> Self {
-        Self { message }
+        Self {
+            remaining_len: 0,
+            expected: Expectation::Message(message),
+            contexts: Vec::new(),
+        }
+    }
+
+    pub fn expected(&self) -> &Expectation {
+        &self.expected
+    }
+
+    /// Context labels from the innermost to the outermost one.
+    pub fn contexts(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.contexts.iter().map(|(_, context)| *context)
     }
+
+    /// Byte offset of the failure within `input`, which must be the input the parser started on.
+    pub fn offset(&self, input: &str) -> usize {
+        input.len().saturating_sub(self.remaining_len)
+    }
+
+    /// Moves the error towards the start of the input by `len` bytes, for errors from parsers that ran on a prefix.
+    pub(crate) fn shift(mut self, len: usize) -> Self {
+        self.remaining_len += len;
+        for (remaining_len, _) in &mut self.contexts {
+            *remaining_len += len;
+        }
+        self
+    }
+
+    /// Renders the error with the offending line of `input` and a caret pointing at the failure.
+    pub fn render(&self, input: &str) -> String {
+        let mut rendered = locate(input, self.offset(input), self.expected.to_string()).to_string();
+        for (remaining_len, context) in &self.contexts {
+            let position = locate(
+                input,
+                input.len().saturating_sub(*remaining_len),
+                String::new(),
+            );
+            rendered.push_str(&format!(
+                "\n  while parsing {} starting in line {}, column {}",
+                context, position.line_number, position.column
+            ));
+        }
+        rendered
+    }
+}
+
+fn locate(input: &str, offset: usize, message: String) -> InputError {
+    let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = input[offset..]
+        .find('\n')
+        .map_or(input.len(), |index| offset + index);
+    let line = input[line_start..line_end].trim_end_matches('\r');
+    let line_number = input[..line_start].matches('\n').count() + 1;
+    InputError::new(
+        line_number,
+        line,
+        (offset - line_start).min(line.len()),
+        message,
+    )
 }
 
-impl FromExternalError<&str, ParseError> for ParseError {
-    fn from_external_error(input: &str, _: nom::error::ErrorKind, error: ParseError) -> Self {
-        ParseError {
-            message: format!("Unable to parse input {}: {}", input, error),
+impl<E: Display> FromExternalError<&str, E> for ParseError {
+    fn from_external_error(input: &str, _: ErrorKind, error: E) -> Self {
+        Self {
+            remaining_len: input.len(),
+            expected: Expectation::Message(error.to_string()),
+            contexts: Vec::new(),
         }
     }
 }
 
 impl nom::error::ParseError<&str> for ParseError {
-    fn from_error_kind(input: &str, _: nom::error::ErrorKind) -> Self {
-        ParseError {
-            message: format!("Unable to parse input {}", input),
+    fn from_error_kind(input: &str, kind: ErrorKind) -> Self {
+        Self {
+            remaining_len: input.len(),
+            expected: Expectation::Kind(kind),
+            contexts: Vec::new(),
         }
     }
 
-    fn append(_: &str, _: nom::error::ErrorKind, other: Self) -> Self {
-        let message = format!("{}: {}", other.message, other.message);
-        Self { message }
+    fn append(_: &str, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: &str, c: char) -> Self {
+        Self {
+            remaining_len: input.len(),
+            expected: Expectation::Char(c),
+            contexts: Vec::new(),
+        }
+    }
+
+    fn or(self, other: Self) -> Self {
+        // Keep the alternative that got further into the input.
+        if other.remaining_len <= self.remaining_len {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl nom::error::ContextError<&str> for ParseError {
+    fn add_context(input: &str, context: &'static str, mut other: Self) -> Self {
+        other.contexts.push((input.len(), context));
+        other
+    }
+}
+
+impl LineError for ParseError {
+    fn remaining_len(&self) -> usize {
+        self.remaining_len
+    }
+
+    fn description(&self) -> String {
+        self.to_string()
     }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.message)
+        write!(f, "{}", self.expected)?;
+        for context in self.contexts() {
+            write!(f, " while parsing {}", context)?;
+        }
+        Ok(())
     }
 }
 
+impl std::error::Error for ParseError {}
+
 pub type Coordinate = (usize, usize);
 
+pub type Point = (isize, isize);
+
 pub trait MatrixVisitor<T> {
     fn visit(&mut self, _: &mut T, _: Coordinate) {}
     fn post_visit(&mut self, _: &mut Matrix<T>) {}
     fn reset(&mut self) {}
 }
 
+#[derive(Clone)]
 pub struct Matrix<T> {
     data: Vec<Vec<T>>,
     rows: usize,
@@ -85,6 +376,11 @@ impl<T: Copy> Matrix<T> {
         }
     }
 
+    /// Matrix of the given size with every element set to `value`.
+    pub fn filled(rows: usize, columns: usize, value: T) -> Self {
+        Self::new(vec![vec![value; columns]; rows])
+    }
+
     pub fn size(&self) -> Coordinate {
         (self.rows, self.columns)
     }
@@ -177,3 +473,103 @@ impl<T: Copy> Matrix<T> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nom::{
+        branch::alt,
+        bytes::complete::tag,
+        character::complete::{char, newline},
+        combinator::cut,
+        error::context,
+        multi::separated_list1,
+        sequence::separated_pair,
+    };
+
+    use super::*;
+
+    fn int<T: FromStr<Err = ParseIntError>>(input: &str) -> IResult<&str, T> {
+        parse_int().parse(input)
+    }
+
+    #[test]
+    fn test_parse_int() {
+        assert_eq!(int::<i64>("-42 rest"), Ok((" rest", -42)));
+        assert_eq!(int::<i8>("+127"), Ok(("", 127)));
+        assert_eq!(int::<usize>("7,8"), Ok((",8", 7)));
+        assert_eq!(parse_as_big_int("12345678901"), Ok(("", 12345678901)));
+    }
+
+    #[test]
+    fn test_parse_int_errors() {
+        let error = |input, kind| nom::Err::Error(nom::error::Error::new(input, kind));
+        assert_eq!(int::<u8>("256"), Err(error("256", ErrorKind::TooLarge)));
+        assert_eq!(int::<i8>("-129"), Err(error("-129", ErrorKind::TooLarge)));
+        assert_eq!(int::<u32>("-1"), Err(error("-1", ErrorKind::Digit)));
+        assert!(parse_as_int("x").is_err());
+        assert!(parse_as_int("+5").is_err());
+    }
+
+    #[test]
+    fn test_try_parse_lines() {
+        let parser = || separated_pair(parse_as_int, tag("|"), parse_as_int);
+        assert_eq!(
+            try_parse_lines("47|53\n97|13", parser()),
+            Ok(vec![(47, 53), (97, 13)])
+        );
+        let error = try_parse_lines("47|53\n97|x3", parser()).unwrap_err();
+        assert_eq!((error.line_number, error.column), (2, 4));
+        assert_eq!(
+            error.to_string(),
+            "Invalid input in line 2, column 4: Digit\n2 | 97|x3\n  |    ^"
+        );
+        let error = try_parse_lines("47|53 ", parser()).unwrap_err();
+        assert_eq!((error.line_number, error.column), (1, 6));
+        assert_eq!(error.message, "Unexpected trailing input");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let input = "47|53\n97|x3";
+        let result: IResult<&str, Vec<(u32, u32)>, ParseError> = context(
+            "rules",
+            separated_list1(
+                newline,
+                context(
+                    "rule",
+                    separated_pair(parse_int(), char('|'), cut(parse_int())),
+                ),
+            ),
+        )(input);
+        let Err(nom::Err::Failure(error)) = result else {
+            panic!("Expected a failure");
+        };
+        assert_eq!(error.offset(input), 9);
+        assert_eq!(error.expected(), &Expectation::Kind(ErrorKind::Digit));
+        assert_eq!(error.contexts().collect::<Vec<_>>(), ["rule", "rules"]);
+        assert_eq!(
+            error.to_string(),
+            "expected a digit while parsing rule while parsing rules"
+        );
+        assert_eq!(
+            error.render(input),
+            "Invalid input in line 2, column 4: expected a digit\n\
+             2 | 97|x3\n  |    ^\n\
+             \x20 while parsing rule starting in line 2, column 1\n\
+             \x20 while parsing rules starting in line 1, column 1"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_alternatives() {
+        let result: IResult<&str, (u32, u32), ParseError> = alt((
+            separated_pair(parse_int(), char('|'), parse_int()),
+            separated_pair(parse_int(), char(','), parse_int()),
+        ))("97x3");
+        let Err(nom::Err::Error(error)) = result else {
+            panic!("Expected an error");
+        };
+        assert_eq!(error.expected(), &Expectation::Char(','));
+        assert_eq!(error.offset("97x3"), 2);
+    }
+}
@@ -0,0 +1,224 @@
+//! Number theory for cycle alignment and modular puzzles.
+//!
+//! All functions widen their arguments to `i128` internally, so intermediate products of 64-bit values cannot
+//! overflow. Results that do not fit the argument type panic.
+
+use std::fmt::Debug;
+
+use crate::scan::Integer;
+
+/// Integers of at most 64 bits, which covers everything the math helpers accept.
+pub trait Number: Integer + Ord + Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn to_i128(self) -> i128;
+    fn from_i128(value: i128) -> Option<Self>;
+}
+
+macro_rules! number {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(value: i128) -> Option<Self> {
+                    value.try_into().ok()
+                }
+            }
+        )*
+    };
+}
+
+number!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+fn narrow<T: Number>(value: i128) -> T {
+    T::from_i128(value)
+        .unwrap_or_else(|| panic!("{} does not fit into {}", value, std::any::type_name::<T>()))
+}
+
+fn gcd_wide(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn lcm_wide(a: i128, b: i128) -> i128 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a.abs() / gcd_wide(a, b))
+        .checked_mul(b.abs())
+        .expect("lcm overflows")
+}
+
+/// Greatest common divisor, always non-negative. `gcd(0, 0)` is 0.
+pub fn gcd<T: Number>(a: T, b: T) -> T {
+    narrow(gcd_wide(a.to_i128(), b.to_i128()))
+}
+
+/// Least common multiple, always non-negative. Zero if either argument is zero.
+pub fn lcm<T: Number>(a: T, b: T) -> T {
+    narrow(lcm_wide(a.to_i128(), b.to_i128()))
+}
+
+/// Greatest common divisor of all values, 0 for an empty slice.
+pub fn gcd_of<T: Number>(values: &[T]) -> T {
+    narrow(
+        values
+            .iter()
+            .fold(0, |gcd, value| gcd_wide(gcd, value.to_i128())),
+    )
+}
+
+/// Least common multiple of all values, e.g. the period after which several cycles align. 1 for an empty slice.
+pub fn lcm_of<T: Number>(values: &[T]) -> T {
+    narrow(
+        values
+            .iter()
+            .fold(1, |lcm, value| lcm_wide(lcm, value.to_i128())),
+    )
+}
+
+/// Extended Euclidean algorithm. Returns `(g, x, y)` with `g = gcd(a, b)` and `a * x + b * y = g`.
+///
+/// The coefficients are returned as `i128` because they may be negative even for unsigned arguments.
+pub fn extended_gcd<T: Number>(a: T, b: T) -> (T, i128, i128) {
+    let (g, x, y) = extended_gcd_wide(a.to_i128(), b.to_i128());
+    (narrow(g), x, y)
+}
+
+fn extended_gcd_wide(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// `a * b mod modulus` for `0 <= a, b < modulus <= 2^64`, computed without overflow.
+fn mul_mod(a: i128, b: i128, modulus: i128) -> i128 {
+    ((a as u128 * b as u128) % modulus as u128) as i128
+}
+
+/// `base^exponent mod modulus` in `0..modulus`. Negative bases are allowed.
+pub fn modpow<T: Number>(base: T, exponent: T, modulus: T) -> T {
+    let modulus = modulus.to_i128();
+    let mut exponent = exponent.to_i128();
+    assert!(modulus > 0, "modulus must be positive");
+    assert!(exponent >= 0, "exponent must not be negative");
+    let mut base = base.to_i128().rem_euclid(modulus);
+    let mut result = 1 % modulus;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exponent >>= 1;
+    }
+    narrow(result)
+}
+
+/// The `x` in `0..modulus` with `a * x ≡ 1 (mod modulus)`, if `a` and `modulus` are coprime.
+pub fn modinv<T: Number>(a: T, modulus: T) -> Option<T> {
+    modinv_wide(a.to_i128(), modulus.to_i128()).map(narrow)
+}
+
+fn modinv_wide(a: i128, modulus: i128) -> Option<i128> {
+    assert!(modulus > 0, "modulus must be positive");
+    let (g, x, _) = extended_gcd_wide(a.rem_euclid(modulus), modulus);
+    (g == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// Solves the system `x ≡ residue (mod modulus)` for all `(residue, modulus)` pairs with the Chinese remainder
+/// theorem. The moduli need not be coprime.
+///
+/// Returns the smallest non-negative solution together with the combined modulus, i.e. the lcm of all moduli,
+/// or `None` if the congruences contradict each other. Residues may be negative or exceed their modulus.
+pub fn crt<T: Number>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut x = 0;
+    let mut combined = 1;
+    for (residue, modulus) in congruences {
+        let modulus = modulus.to_i128();
+        assert!(modulus > 0, "modulus must be positive");
+        let residue = residue.to_i128().rem_euclid(modulus);
+        // Find k with x + combined * k ≡ residue (mod modulus).
+        let g = gcd_wide(combined, modulus);
+        let difference = (residue - x).rem_euclid(modulus);
+        if difference % g != 0 {
+            return None;
+        }
+        let reduced = modulus / g;
+        let inverse = modinv_wide(combined / g, reduced).unwrap();
+        let k = mul_mod((difference / g) % reduced, inverse, reduced);
+        let next = lcm_wide(combined, modulus);
+        x = (x + mul_mod(combined % next, k, next)) % next;
+        combined = next;
+    }
+    Some((narrow(x), narrow(combined)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12u32, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0usize, 0), 0);
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(0i32, 5), 0);
+        assert_eq!(gcd_of(&[24u32, 60, 36]), 12);
+        assert_eq!(lcm_of(&[2u64, 3, 4, 5]), 60);
+        assert_eq!(lcm_of::<u64>(&[]), 1);
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        for (a, b) in [(240i64, 46), (46, 240), (-15, 25), (7, 0), (0, 7)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a as i128 * x + b as i128 * y, g as i128);
+        }
+        let (g, x, y) = extended_gcd(3u32, 5);
+        assert_eq!((g, 3 * x + 5 * y), (1, 1));
+    }
+
+    #[test]
+    fn test_modular() {
+        assert_eq!(modpow(2u64, 10, 1000), 24);
+        assert_eq!(modpow(-2i32, 3, 5), 2);
+        assert_eq!(modpow(5u32, 0, 1), 0);
+        assert_eq!(modpow(u64::MAX - 1, u64::MAX, u64::MAX), u64::MAX - 1);
+        assert_eq!(modinv(3u32, 11), Some(4));
+        assert_eq!(modinv(-3i64, 11), Some(7));
+        assert_eq!(modinv(6u32, 9), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        // Two discs with 5 and 2 positions that start at positions 4 and 1 align at time 5.
+        assert_eq!(crt(&[(-(1 + 4), 5i64), (-(2 + 1), 2)]), Some((5, 10)));
+        assert_eq!(crt(&[(2u64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(2u32, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1u32, 4), (2, 6)]), None);
+        assert_eq!(crt::<u32>(&[]), Some((0, 1)));
+    }
+}
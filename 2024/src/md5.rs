@@ -0,0 +1,306 @@
+//! MD5 as specified in RFC 1321, for puzzles that mine hashes like `abcdef609043`.
+
+use std::{fmt, thread};
+
+const SHIFTS: [u32; 64] = [
+    7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, //
+    5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, 5, 9, 14, 20, //
+    4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, //
+    6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21, //
+];
+
+/// `floor(abs(sin(i + 1)) * 2^32)` for every round `i`.
+const CONSTANTS: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+/// Indices searched per thread before the threads synchronise to check whether enough matches were found.
+const BATCH_SIZE: u64 = 4096;
+
+/// Streaming MD5 hasher. Feed it with `update` and finish with `finalize`.
+#[derive(Clone, Debug)]
+pub struct Md5 {
+    state: [u32; 4],
+    buffer: [u8; 64],
+    buffered: usize,
+    length: u64,
+}
+
+impl Default for Md5 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Md5 {
+    pub fn new() -> Self {
+        Self {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            buffer: [0; 64],
+            buffered: 0,
+            length: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+        if self.buffered > 0 {
+            let taken = data.len().min(64 - self.buffered);
+            self.buffer[self.buffered..self.buffered + taken].copy_from_slice(&data[..taken]);
+            self.buffered += taken;
+            data = &data[taken..];
+            if self.buffered < 64 {
+                return;
+            }
+            let block = self.buffer;
+            self.process(&block);
+            self.buffered = 0;
+        }
+        let mut blocks = data.chunks_exact(64);
+        for block in blocks.by_ref() {
+            self.process(block.try_into().unwrap());
+        }
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffered = rest.len();
+    }
+
+    pub fn finalize(mut self) -> Digest {
+        let bit_length = self.length.wrapping_mul(8);
+        let padding = if self.buffered < 56 {
+            56 - self.buffered
+        } else {
+            120 - self.buffered
+        };
+        let mut tail = [0; 72];
+        tail[0] = 0x80;
+        tail[padding..padding + 8].copy_from_slice(&bit_length.to_le_bytes());
+        self.update(&tail[..padding + 8]);
+        debug_assert_eq!(self.buffered, 0);
+        let mut digest = [0; 16];
+        for (bytes, word) in digest.chunks_exact_mut(4).zip(self.state) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        Digest(digest)
+    }
+
+    fn process(&mut self, block: &[u8; 64]) {
+        let words: [u32; 16] = std::array::from_fn(|i| {
+            u32::from_le_bytes(block[i * 4..i * 4 + 4].try_into().unwrap())
+        });
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for round in 0..64 {
+            let (f, g) = match round / 16 {
+                0 => ((b & c) | (!b & d), round),
+                1 => ((d & b) | (!d & c), (5 * round + 1) % 16),
+                2 => (b ^ c ^ d, (3 * round + 5) % 16),
+                _ => (c ^ (b | !d), (7 * round) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(CONSTANTS[round])
+                .wrapping_add(words[g])
+                .rotate_left(SHIFTS[round]);
+            (a, b, c, d) = (d, b.wrapping_add(rotated), b, c);
+        }
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d]) {
+            *state = state.wrapping_add(value);
+        }
+    }
+}
+
+/// MD5 of `data` in one go.
+pub fn md5(data: &[u8]) -> Digest {
+    let mut hasher = Md5::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Digest(pub [u8; 16]);
+
+impl Digest {
+    /// The hexadecimal digit at `index` in `0..32`, as a value in `0..16`.
+    pub fn nibble(&self, index: usize) -> u8 {
+        let byte = self.0[index / 2];
+        if index.is_multiple_of(2) {
+            byte >> 4
+        } else {
+            byte & 0x0f
+        }
+    }
+
+    pub fn nibbles(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..32).map(|index| self.nibble(index))
+    }
+
+    /// Number of leading `0` digits in the hexadecimal representation.
+    pub fn leading_zeros(&self) -> usize {
+        self.nibbles().take_while(|nibble| *nibble == 0).count()
+    }
+
+    /// The 32 lowercase hexadecimal digits, e.g. for hashing the hash again.
+    pub fn to_hex(&self) -> [u8; 32] {
+        std::array::from_fn(|index| hex_digit(self.nibble(index)) as u8)
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+/// The lowercase hexadecimal digit for a value in `0..16`.
+pub fn hex_digit(nibble: u8) -> char {
+    char::from_digit(nibble as u32, 16).unwrap()
+}
+
+/// The first `count` indices `i` for which the hash of `prefix` followed by the decimal `i` satisfies `predicate`,
+/// together with their hashes, e.g. `find_indices("abcdef", 1, |digest| digest.leading_zeros() >= 5)`.
+///
+/// Spreads the hashing over all available cores.
+pub fn find_indices<P>(prefix: &str, count: usize, predicate: P) -> Vec<(u64, Digest)>
+where
+    P: Fn(&Digest) -> bool + Sync,
+{
+    let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+    search(prefix, count, threads as u64, predicate)
+}
+
+fn search<P>(prefix: &str, count: usize, threads: u64, predicate: P) -> Vec<(u64, Digest)>
+where
+    P: Fn(&Digest) -> bool + Sync,
+{
+    let mut base = Md5::new();
+    base.update(prefix.as_bytes());
+    let mut matches = Vec::new();
+    let mut start = 0;
+    // Every round searches one contiguous range, so all matches before its end are known afterwards.
+    while matches.len() < count {
+        let results = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|worker| {
+                    let (base, predicate) = (&base, &predicate);
+                    let first = start + worker * BATCH_SIZE;
+                    scope.spawn(move || {
+                        (first..first + BATCH_SIZE)
+                            .filter_map(|index| {
+                                let digest = hash_index(base, index);
+                                predicate(&digest).then_some((index, digest))
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect::<Vec<_>>()
+        });
+        matches.extend(results);
+        start += threads * BATCH_SIZE;
+    }
+    matches.truncate(count);
+    matches
+}
+
+fn hash_index(base: &Md5, index: u64) -> Digest {
+    let mut digits = [0; 20];
+    let mut position = digits.len();
+    let mut rest = index;
+    loop {
+        position -= 1;
+        digits[position] = b'0' + (rest % 10) as u8;
+        rest /= 10;
+        if rest == 0 {
+            break;
+        }
+    }
+    let mut hasher = base.clone();
+    hasher.update(&digits[position..]);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc_1321_vectors() {
+        let vectors = [
+            ("", "d41d8cd98f00b204e9800998ecf8427e"),
+            ("a", "0cc175b9c0f1b6a831c399e269772661"),
+            ("abc", "900150983cd24fb0d6963f7d28e17f72"),
+            ("message digest", "f96b697d7cb7938d525a2f31aaf161d0"),
+            (
+                "abcdefghijklmnopqrstuvwxyz",
+                "c3fcd3d76192e4007dfb496cca67e13b",
+            ),
+            (
+                "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789",
+                "d174ab98d277d9f5a5611c2c9f419d9f",
+            ),
+            (
+                "12345678901234567890123456789012345678901234567890123456789012345678901234567890",
+                "57edf4a22be3c955ac49da2e2107b67a",
+            ),
+        ];
+        for (input, expected) in vectors {
+            assert_eq!(md5(input.as_bytes()).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_streaming() {
+        let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let expected = md5(&data);
+        for split in [1, 55, 56, 63, 64, 65, 128, 999] {
+            let mut hasher = Md5::new();
+            for chunk in data.chunks(split) {
+                hasher.update(chunk);
+            }
+            assert_eq!(hasher.finalize(), expected);
+        }
+    }
+
+    #[test]
+    fn test_nibbles() {
+        let digest = md5(b"abcdef609043");
+        assert_eq!(digest.leading_zeros(), 5);
+        assert_eq!(digest.nibble(5), 1);
+        assert_eq!(digest.nibble(6), 0xd);
+        assert_eq!(hex_digit(digest.nibble(6)), 'd');
+        assert_eq!(&digest.to_hex(), b"000001dbbfa3a5c83a2d506429c7b00e");
+    }
+
+    #[test]
+    fn test_find_indices() {
+        let found = |threads| {
+            search("abc", 5, threads, |digest| digest.leading_zeros() >= 3)
+                .into_iter()
+                .map(|(index, digest)| (index, hex_digit(digest.nibble(3))))
+                .collect::<Vec<_>>()
+        };
+        let expected = [
+            (2196, 'd'),
+            (3527, '8'),
+            (10201, 'd'),
+            (33464, '6'),
+            (34211, '0'),
+        ];
+        assert_eq!(found(1), expected);
+        assert_eq!(found(3), expected);
+        assert_eq!(
+            find_indices("abcdef", 1, |digest| digest.leading_zeros() >= 5)[0].0,
+            609043
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Caches for memoised recursion, either threaded through explicitly or generated by `memoize!`.
+
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// Cache from arguments to results of a recursive computation.
+///
+/// A bounded cache is cleared completely once it is full, which keeps the memory bounded without any
+/// bookkeeping per entry. Debug builds count hits and misses.
+#[derive(Clone, Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    capacity: Option<usize>,
+    #[cfg(debug_assertions)]
+    stats: Stats,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.hits + self.misses;
+        let rate = if total == 0 {
+            0.0
+        } else {
+            self.hits as f64 * 100.0 / total as f64
+        };
+        write!(
+            f,
+            "{} hits, {} misses ({:.1}% hit rate)",
+            self.hits, self.misses, rate
+        )
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            capacity: None,
+            #[cfg(debug_assertions)]
+            stats: Stats::default(),
+        }
+    }
+
+    /// A cache that holds at most `capacity` entries.
+    pub fn bounded(capacity: usize) -> Self {
+        assert!(capacity > 0);
+        Self {
+            capacity: Some(capacity),
+            ..Self::new()
+        }
+    }
+
+    /// The cached value for `key`, computed with `compute` on a miss. `compute` receives the cache for its
+    /// recursive calls, e.g. `memo.get_or_compute(n, |memo| count(memo, n - 1) + count(memo, n - 2))`.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.lookup(&key) {
+            return value;
+        }
+        let value = compute(self);
+        self.insert(key, value.clone());
+        value
+    }
+
+    /// The cached value for `key`, counted as a hit or a miss.
+    pub fn lookup(&mut self, key: &K) -> Option<V> {
+        let value = self.cache.get(key).cloned();
+        #[cfg(debug_assertions)]
+        match value {
+            Some(_) => self.stats.hits += 1,
+            None => self.stats.misses += 1,
+        }
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        if self
+            .capacity
+            .is_some_and(|capacity| self.cache.len() >= capacity && !self.cache.contains_key(&key))
+        {
+            self.cache.clear();
+        }
+        self.cache.insert(key, value);
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Removes all entries, e.g. between parts whose recursion depends on different parameters. Keeps the
+    /// statistics.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Hits and misses so far, only available in debug builds.
+    pub fn stats(&self) -> Option<Stats> {
+        #[cfg(debug_assertions)]
+        return Some(self.stats);
+        #[cfg(not(debug_assertions))]
+        None
+    }
+}
+
+/// Defines a memoised function together with the thread-local `Memo` it uses, keyed by its arguments (as a tuple
+/// for several arguments), e.g. `memoize! { static CACHE: Memo<(u64, u32), u64>; fn count(stone: u64, blinks: u32)
+/// -> u64 { .. } }`. The cache is created with `Memo::new()` unless an initializer like `= Memo::bounded(1000)`
+/// follows its type, and can be cleared with `CACHE.with_borrow_mut(|memo| memo.clear())`.
+#[macro_export]
+macro_rules! memoize {
+    (
+        static $cache:ident: Memo<$k:ty, $v:ty> $(= $init:expr)?;
+        $vis:vis fn $name:ident($($arg:ident: $t:ty),+ $(,)?) -> $ret:ty $body:block
+    ) => {
+        ::std::thread_local! {
+            static $cache: ::std::cell::RefCell<$crate::memo::Memo<$k, $v>> =
+                ::std::cell::RefCell::new($crate::memoize!(@init $($init)?));
+        }
+
+        $vis fn $name($($arg: $t),+) -> $ret {
+            let key: $k = $crate::memoize!(@key $($arg),+);
+            if let Some(value) = $cache.with_borrow_mut(|memo| memo.lookup(&key)) {
+                return value;
+            }
+            // The cache must not stay borrowed while the body recurses.
+            fn compute($($arg: $t),+) -> $ret $body
+            let value = compute($($arg),+);
+            $cache.with_borrow_mut(|memo| memo.insert(key, ::std::clone::Clone::clone(&value)));
+            value
+        }
+    };
+
+    (@init) => { $crate::memo::Memo::new() };
+    (@init $init:expr) => {{
+        use $crate::memo::Memo;
+        $init
+    }};
+
+    (@key $arg:ident) => { ::std::clone::Clone::clone(&$arg) };
+    (@key $($arg:ident),+) => { ($(::std::clone::Clone::clone(&$arg)),+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    crate::memoize! {
+        static FIBONACCI: Memo<u32, u128>;
+        fn fibonacci(n: u32) -> u128 {
+            if n < 2 {
+                n as u128
+            } else {
+                fibonacci(n - 1) + fibonacci(n - 2)
+            }
+        }
+    }
+
+    crate::memoize! {
+        static BINOMIAL: Memo<(u64, u64), u64> = Memo::bounded(8);
+        fn binomial(n: u64, k: u64) -> u64 {
+            if k == 0 || k == n {
+                1
+            } else {
+                binomial(n - 1, k - 1) + binomial(n - 1, k)
+            }
+        }
+    }
+
+    fn paths(memo: &mut Memo<(usize, usize), u64>, row: usize, column: usize) -> u64 {
+        if row == 0 || column == 0 {
+            return 1;
+        }
+        memo.get_or_compute((row, column), |memo| {
+            paths(memo, row - 1, column) + paths(memo, row, column - 1)
+        })
+    }
+
+    #[test]
+    fn test_memoize() {
+        assert_eq!(fibonacci(150), 9_969_216_677_189_303_386_214_405_760_200);
+        assert_eq!(FIBONACCI.with_borrow(|memo| memo.len()), 151);
+        FIBONACCI.with_borrow_mut(|memo| memo.clear());
+        assert!(FIBONACCI.with_borrow(|memo| memo.is_empty()));
+
+        assert_eq!(binomial(30, 15), 155_117_520);
+        assert!(BINOMIAL.with_borrow(|memo| memo.len()) <= 8);
+    }
+
+    #[test]
+    fn test_get_or_compute() {
+        let mut memo = Memo::new();
+        assert_eq!(paths(&mut memo, 16, 16), 601_080_390);
+        assert_eq!(memo.len(), 256);
+        assert_eq!(memo.get(&(2, 2)), Some(&6));
+        #[cfg(debug_assertions)]
+        assert_eq!(
+            memo.stats(),
+            Some(Stats {
+                hits: 225,
+                misses: 256
+            })
+        );
+        #[cfg(not(debug_assertions))]
+        assert_eq!(memo.stats(), None);
+    }
+
+    #[test]
+    fn test_bounded() {
+        let mut memo = Memo::bounded(2);
+        memo.insert(1, 'a');
+        memo.insert(2, 'b');
+        memo.insert(2, 'c');
+        assert_eq!(memo.len(), 2);
+        memo.insert(3, 'd');
+        assert_eq!((memo.len(), memo.get(&3)), (1, Some(&'d')));
+        assert_eq!(
+            Stats { hits: 3, misses: 1 }.to_string(),
+            "3 hits, 1 misses (75.0% hit rate)"
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! Solver for equations like `3267: 81 40 27` where operators have to be inserted between the operands.
+//!
+//! Expressions are evaluated strictly from left to right without precedence.
+
+use std::fmt;
+
+use crate::digits::{checked_concat, strip_suffix_digits};
+
+/// Left operands that produce a result with a given right operand.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Inverse {
+    None,
+    Exactly(u64),
+    /// Every left operand works, e.g. for a multiplication with zero.
+    Any,
+}
+
+/// An operator given by its forward evaluation and its inverse with respect to the left operand. Both return
+/// `None` or `Inverse::None` if there is no result within `u64`.
+#[derive(Clone, Copy, Debug)]
+pub struct Operator {
+    pub symbol: &'static str,
+    pub apply: fn(u64, u64) -> Option<u64>,
+    pub invert: fn(u64, u64) -> Inverse,
+}
+
+impl Operator {
+    pub const ADD: Operator = Operator::new("+", u64::checked_add, |result, operand| match result
+        .checked_sub(operand)
+    {
+        Some(left) => Inverse::Exactly(left),
+        None => Inverse::None,
+    });
+    pub const MULTIPLY: Operator = Operator::new("*", u64::checked_mul, |result, operand| {
+        match (result, operand) {
+            (0, 0) => Inverse::Any,
+            (_, 0) => Inverse::None,
+            _ if result % operand == 0 => Inverse::Exactly(result / operand),
+            _ => Inverse::None,
+        }
+    });
+    pub const CONCATENATE: Operator =
+        Operator::new(
+            "||",
+            checked_concat,
+            |result, operand| match strip_suffix_digits(result, operand) {
+                Some(left) => Inverse::Exactly(left),
+                None => Inverse::None,
+            },
+        );
+
+    pub const fn new(
+        symbol: &'static str,
+        apply: fn(u64, u64) -> Option<u64>,
+        invert: fn(u64, u64) -> Inverse,
+    ) -> Self {
+        Self {
+            symbol,
+            apply,
+            invert,
+        }
+    }
+}
+
+impl PartialEq for Operator {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
+}
+
+/// Operands with the operators between them, displayed like `81 * 40 + 27`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Expression {
+    pub operands: Vec<u64>,
+    pub operators: Vec<Operator>,
+}
+
+impl Expression {
+    /// Evaluates from left to right, or `None` on overflow.
+    pub fn evaluate(&self) -> Option<u64> {
+        self.operators
+            .iter()
+            .zip(&self.operands[1..])
+            .try_fold(self.operands[0], |value, (operator, operand)| {
+                (operator.apply)(value, *operand)
+            })
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.operands[0])?;
+        for (operator, operand) in self.operators.iter().zip(&self.operands[1..]) {
+            write!(f, " {} {}", operator.symbol, operand)?;
+        }
+        Ok(())
+    }
+}
+
+/// Searches operator sequences backwards from the target, so that every step is pruned by the inverse of the
+/// operator, e.g. subtraction must not underflow and division must leave no remainder.
+pub struct Solver<'a> {
+    operators: &'a [Operator],
+    limit: u64,
+}
+
+impl<'a> Solver<'a> {
+    /// Solver for the given operators, which are tried in order. The first operator yielding a solution wins.
+    pub fn new(operators: &'a [Operator]) -> Self {
+        Self {
+            operators,
+            limit: u64::MAX,
+        }
+    }
+
+    /// Rejects expressions with an intermediate value above `limit`.
+    pub fn with_limit(mut self, limit: u64) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    pub fn is_solvable(&self, target: u64, operands: &[u64]) -> bool {
+        self.solve(target, operands).is_some()
+    }
+
+    /// An expression over `operands` that evaluates to `target`, if there is one.
+    pub fn solve(&self, target: u64, operands: &[u64]) -> Option<Expression> {
+        let mut operators = Vec::with_capacity(operands.len().saturating_sub(1));
+        let solved = !operands.is_empty()
+            && target <= self.limit
+            && self.backwards(target, operands, &mut operators);
+        solved.then(|| Expression {
+            operands: operands.to_vec(),
+            operators,
+        })
+    }
+
+    /// Pushes the operators in forward order while returning from a successful search.
+    fn backwards(&self, target: u64, operands: &[u64], operators: &mut Vec<Operator>) -> bool {
+        let (last, rest) = operands.split_last().unwrap();
+        if rest.is_empty() {
+            return *last == target;
+        }
+        for operator in self.operators {
+            let found = match (operator.invert)(target, *last) {
+                Inverse::Exactly(left) => {
+                    left <= self.limit && self.backwards(left, rest, operators)
+                }
+                Inverse::Any => self.forwards(rest[0], &rest[1..], operators),
+                Inverse::None => false,
+            };
+            if found {
+                operators.push(*operator);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Finds any operators that evaluate `operands` within the limit, used when the remaining prefix is
+    /// irrelevant for the result.
+    fn forwards(&self, value: u64, operands: &[u64], operators: &mut Vec<Operator>) -> bool {
+        if value > self.limit {
+            return false;
+        }
+        let Some((first, rest)) = operands.split_first() else {
+            return true;
+        };
+        for operator in self.operators {
+            if let Some(next) = (operator.apply)(value, *first) {
+                operators.push(*operator);
+                if self.forwards(next, rest, operators) {
+                    return true;
+                }
+                operators.pop();
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASIC: [Operator; 2] = [Operator::ADD, Operator::MULTIPLY];
+    const EXTENDED: [Operator; 3] = [Operator::ADD, Operator::MULTIPLY, Operator::CONCATENATE];
+
+    fn witness(operators: &[Operator], target: u64, operands: &[u64]) -> Option<String> {
+        let expression = Solver::new(operators).solve(target, operands)?;
+        assert_eq!(expression.evaluate(), Some(target));
+        Some(expression.to_string())
+    }
+
+    #[test]
+    fn test_solve() {
+        assert_eq!(witness(&BASIC, 190, &[10, 19]), Some("10 * 19".to_string()));
+        assert_eq!(
+            witness(&BASIC, 3267, &[81, 40, 27]),
+            Some("81 * 40 + 27".to_string())
+        );
+        assert_eq!(witness(&BASIC, 7290, &[6, 8, 6, 15]), None);
+        assert_eq!(
+            witness(&EXTENDED, 7290, &[6, 8, 6, 15]),
+            Some("6 * 8 || 6 * 15".to_string())
+        );
+        assert_eq!(
+            witness(&EXTENDED, 156, &[15, 6]),
+            Some("15 || 6".to_string())
+        );
+        assert_eq!(
+            witness(&EXTENDED, 192, &[17, 8, 14]),
+            Some("17 || 8 + 14".to_string())
+        );
+        assert_eq!(witness(&EXTENDED, 21037, &[9, 7, 18, 13]), None);
+        assert_eq!(witness(&BASIC, 5, &[5]), Some("5".to_string()));
+        assert_eq!(witness(&BASIC, 5, &[]), None);
+    }
+
+    #[test]
+    fn test_zero_operands() {
+        assert_eq!(
+            witness(&BASIC, 0, &[3, 4, 0]),
+            Some("3 + 4 * 0".to_string())
+        );
+        assert_eq!(
+            witness(&BASIC, 4, &[3, 0, 4]),
+            Some("3 * 0 + 4".to_string())
+        );
+    }
+
+    #[test]
+    fn test_custom_operators_and_limits() {
+        let subtract = Operator::new("-", u64::checked_sub, |result, operand| {
+            match result.checked_add(operand) {
+                Some(left) => Inverse::Exactly(left),
+                None => Inverse::None,
+            }
+        });
+        assert_eq!(
+            witness(&[subtract, Operator::ADD], 3, &[10, 9, 2]),
+            Some("10 - 9 + 2".to_string())
+        );
+        let solver = Solver::new(&[Operator::MULTIPLY]);
+        assert!(solver.is_solvable(u64::MAX, &[u64::MAX, 1]));
+        assert!(!solver.is_solvable(0, &[u64::MAX, 2, 0]));
+        assert!(!Solver::new(&BASIC)
+            .with_limit(100)
+            .is_solvable(200, &[10, 20]));
+        assert!(!Solver::new(&BASIC)
+            .with_limit(100)
+            .is_solvable(10, &[200, 0, 10]));
+        assert!(Solver::new(&BASIC)
+            .with_limit(100)
+            .is_solvable(10, &[20, 0, 10]));
+    }
+}
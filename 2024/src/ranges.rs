@@ -0,0 +1,351 @@
+//! Sets of integers stored as sorted, disjoint ranges, e.g. for blacklists or seed maps.
+//!
+//! Ranges are kept half-open in `i128` internally, so inclusive ranges up to `T::MAX` need no special casing.
+
+use std::{
+    marker::PhantomData,
+    ops::{Bound, RangeBounds, RangeInclusive},
+};
+
+use crate::math::Number;
+
+/// Converts any range of `T` into half-open bounds. Unbounded ends extend to the limits of `T`.
+fn bounds<T: Number>(range: impl RangeBounds<T>) -> (i128, i128) {
+    let start = match range.start_bound() {
+        Bound::Included(start) => start.to_i128(),
+        Bound::Excluded(start) => start.to_i128() + 1,
+        Bound::Unbounded => T::MIN.to_i128(),
+    };
+    let end = match range.end_bound() {
+        Bound::Included(end) => end.to_i128() + 1,
+        Bound::Excluded(end) => end.to_i128(),
+        Bound::Unbounded => T::MAX.to_i128() + 1,
+    };
+    (start, end)
+}
+
+fn inclusive<T: Number>((start, end): (i128, i128)) -> RangeInclusive<T> {
+    let narrow = |value| {
+        T::from_i128(value)
+            .unwrap_or_else(|| panic!("{} is outside of {}", value, std::any::type_name::<T>()))
+    };
+    narrow(start)..=narrow(end - 1)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RangeSet<T> {
+    ranges: Vec<(i128, i128)>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Number> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Number, R: RangeBounds<T>> FromIterator<R> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(ranges: I) -> Self {
+        let mut set = Self::new();
+        for range in ranges {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+impl<T: Number> RangeSet<T> {
+    pub fn new() -> Self {
+        Self {
+            ranges: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Adds all values of `range`, merging it with overlapping and adjacent ranges.
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        self.insert_bounds(bounds(range));
+    }
+
+    fn insert_bounds(&mut self, (mut start, mut end): (i128, i128)) {
+        if start >= end {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.1 < start);
+        let last = self.ranges.partition_point(|r| r.0 <= end);
+        if first < last {
+            start = start.min(self.ranges[first].0);
+            end = end.max(self.ranges[last - 1].1);
+        }
+        self.ranges.splice(first..last, [(start, end)]);
+    }
+
+    /// Removes all values of `range`, splitting a range that contains it.
+    pub fn remove(&mut self, range: impl RangeBounds<T>) {
+        self.remove_bounds(bounds(range));
+    }
+
+    fn remove_bounds(&mut self, (start, end): (i128, i128)) {
+        if start >= end {
+            return;
+        }
+        let first = self.ranges.partition_point(|r| r.1 <= start);
+        let last = self.ranges.partition_point(|r| r.0 < end);
+        if first == last {
+            return;
+        }
+        let left = (self.ranges[first].0, start);
+        let right = (end, self.ranges[last - 1].1);
+        let pieces = [left, right].into_iter().filter(|(start, end)| start < end);
+        self.ranges.splice(first..last, pieces);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.range_containing(value).is_some()
+    }
+
+    /// The maximal range of the set that contains `value`.
+    pub fn range_containing(&self, value: T) -> Option<RangeInclusive<T>> {
+        let value = value.to_i128();
+        let index = self.ranges.partition_point(|r| r.1 <= value);
+        self.ranges
+            .get(index)
+            .filter(|r| r.0 <= value)
+            .map(|r| inclusive(*r))
+    }
+
+    /// The disjoint ranges in ascending order, with gaps between each other.
+    pub fn ranges(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|r| inclusive(*r))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges().next().map(|r| *r.start())
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|r| *inclusive(*r).end())
+    }
+
+    /// Number of values in the set. Returned as `u128` because all values of a 64-bit type do not fit into it.
+    pub fn len(&self) -> u128 {
+        self.ranges.iter().map(|r| (r.1 - r.0) as u128).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for range in &other.ranges {
+            union.insert_bounds(*range);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = Self::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (self.ranges[i], other.ranges[j]);
+            let (start, end) = (a.0.max(b.0), a.1.min(b.1));
+            if start < end {
+                intersection.ranges.push((start, end));
+            }
+            if a.1 < b.1 {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        intersection
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for range in &other.ranges {
+            difference.remove_bounds(*range);
+        }
+        difference
+    }
+
+    /// All values of `T` that are not in the set.
+    pub fn complement(&self) -> Self {
+        let mut complement = Self::new();
+        complement.insert(..);
+        complement.difference(self)
+    }
+
+    /// The set after moving every value through `table`.
+    pub fn map(&self, table: &OffsetTable<T>) -> Self {
+        let mut mapped = Self::new();
+        for range in &self.ranges {
+            table.map_bounds(*range, |start, end| {
+                // Fails if the piece leaves the domain of `T`.
+                inclusive::<T>((start, end));
+                mapped.insert_bounds((start, end));
+            });
+        }
+        mapped
+    }
+}
+
+/// Piecewise translation of values, e.g. a seed-to-soil map. Values outside of all source ranges stay unchanged.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OffsetTable<T> {
+    /// Disjoint half-open source ranges with the offset added to their values, sorted by start.
+    entries: Vec<(i128, i128, i128)>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: Number> OffsetTable<T> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Maps `source` to the range of the same length beginning at `destination`. Panics if `source` overlaps a
+    /// source range added before.
+    pub fn insert(&mut self, source: impl RangeBounds<T>, destination: T) {
+        let (start, end) = bounds(source);
+        if start >= end {
+            return;
+        }
+        let index = self.entries.partition_point(|entry| entry.1 <= start);
+        assert!(
+            self.entries.get(index).is_none_or(|entry| entry.0 >= end),
+            "source ranges of an offset table must not overlap"
+        );
+        self.entries
+            .insert(index, (start, end, destination.to_i128() - start));
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let value = value.to_i128();
+        let index = self.entries.partition_point(|entry| entry.1 <= value);
+        let offset = match self.entries.get(index) {
+            Some(entry) if entry.0 <= value => entry.2,
+            _ => 0,
+        };
+        *inclusive::<T>((value + offset, value + offset + 1)).start()
+    }
+
+    /// Calls `emit` with the translated pieces of the half-open range, splitting it at the table's boundaries.
+    fn map_bounds(&self, (mut start, end): (i128, i128), mut emit: impl FnMut(i128, i128)) {
+        let mut index = self.entries.partition_point(|entry| entry.1 <= start);
+        while start < end {
+            match self.entries.get(index) {
+                Some(&(entry_start, entry_end, offset)) if entry_start <= start => {
+                    let piece_end = end.min(entry_end);
+                    emit(start + offset, piece_end + offset);
+                    start = piece_end;
+                    index += 1;
+                }
+                Some(&(entry_start, _, _)) if entry_start < end => {
+                    emit(start, entry_start);
+                    start = entry_start;
+                }
+                _ => {
+                    emit(start, end);
+                    start = end;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges<T: Number>(set: &RangeSet<T>) -> Vec<RangeInclusive<T>> {
+        set.ranges().collect()
+    }
+
+    #[test]
+    fn test_insert_and_remove() {
+        let mut set: RangeSet<u32> = [5..=8, 0..=2, 4..=7].into_iter().collect();
+        assert_eq!(ranges(&set), [0..=2, 4..=8]);
+        set.insert(3..4);
+        assert_eq!(ranges(&set), [0..=8]);
+        set.insert(10..10);
+        assert_eq!(set.len(), 9);
+
+        set.remove(3..=5);
+        assert_eq!(ranges(&set), [0..=2, 6..=8]);
+        set.remove(0..1);
+        set.remove(8..);
+        assert_eq!(ranges(&set), [1..=2, 6..=7]);
+        set.remove(..);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_lookup() {
+        let set: RangeSet<i64> = [-5..=-1, 3..=3].into_iter().collect();
+        assert!(!set.contains(-6));
+        assert!(set.contains(-5));
+        assert!(set.contains(-1));
+        assert!(!set.contains(0));
+        assert_eq!(set.range_containing(3), Some(3..=3));
+        assert_eq!(set.range_containing(4), None);
+        assert_eq!((set.min(), set.max()), (Some(-5), Some(3)));
+    }
+
+    #[test]
+    fn test_domain_limits() {
+        let mut set = RangeSet::<u8>::new();
+        set.insert(250..=255);
+        set.insert(..=3);
+        assert_eq!(ranges(&set), [0..=3, 250..=255]);
+        assert_eq!(ranges(&set.complement()), [4..=249]);
+        set.insert(..);
+        assert_eq!(set.len(), 256);
+        assert!(set.complement().is_empty());
+
+        let blacklist: RangeSet<u32> = [5..=8, 0..=2, 4..=7].into_iter().collect();
+        let allowed = blacklist.complement();
+        assert_eq!(allowed.min(), Some(3));
+        assert_eq!(allowed.len(), u32::MAX as u128 + 1 - 8);
+        assert_eq!(RangeSet::<u64>::from_iter([..]).len(), 1 << 64);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: RangeSet<i32> = [0..=9, 20..=29].into_iter().collect();
+        let b: RangeSet<i32> = [5..=24, 29..=35].into_iter().collect();
+        assert_eq!(ranges(&a.union(&b)), [0..=35]);
+        assert_eq!(ranges(&a.intersection(&b)), [5..=9, 20..=24, 29..=29]);
+        assert_eq!(ranges(&a.difference(&b)), [0..=4, 25..=28]);
+        assert_eq!(ranges(&b.difference(&a)), [10..=19, 30..=35]);
+        assert!(a.intersection(&RangeSet::new()).is_empty());
+        assert_eq!(a.difference(&RangeSet::new()), a);
+    }
+
+    #[test]
+    fn test_offset_table() {
+        let mut seed_to_soil = OffsetTable::new();
+        seed_to_soil.insert(98..98 + 2, 50u64);
+        seed_to_soil.insert(50..50 + 48, 52);
+        assert_eq!(
+            [0, 49, 50, 79, 97, 98, 99, 100].map(|seed| seed_to_soil.map(seed)),
+            [0, 49, 52, 81, 99, 50, 51, 100]
+        );
+
+        let seeds: RangeSet<u64> = [79..=79 + 13, 45..=55, 96..=101].into_iter().collect();
+        let soil = seeds.map(&seed_to_soil);
+        assert_eq!(ranges(&soil), [45..=57, 81..=94, 98..=101]);
+        assert_eq!(soil.len(), seeds.len());
+    }
+
+    #[test]
+    #[should_panic(expected = "must not overlap")]
+    fn test_overlapping_offsets() {
+        let mut table = OffsetTable::new();
+        table.insert(10..20, 0u32);
+        table.insert(19..=19, 100);
+    }
+}
@@ -0,0 +1,291 @@
+//! Operations on axis-aligned rectangles of a `Matrix`, e.g. "toggle 0,0 through 999,0".
+
+use std::ops::{AddAssign, Not, RangeInclusive};
+
+use crate::{Coordinate, Matrix};
+
+/// Rectangle given by two inclusive corners.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rectangle {
+    pub top_left: Coordinate,
+    pub bottom_right: Coordinate,
+}
+
+impl Rectangle {
+    /// Rectangle spanned by any two opposite corners.
+    pub fn new(a: Coordinate, b: Coordinate) -> Self {
+        Self {
+            top_left: (a.0.min(b.0), a.1.min(b.1)),
+            bottom_right: (a.0.max(b.0), a.1.max(b.1)),
+        }
+    }
+
+    pub fn rows(&self) -> RangeInclusive<usize> {
+        self.top_left.0..=self.bottom_right.0
+    }
+
+    pub fn columns(&self) -> RangeInclusive<usize> {
+        self.top_left.1..=self.bottom_right.1
+    }
+
+    pub fn area(&self) -> usize {
+        (self.bottom_right.0 - self.top_left.0 + 1) * (self.bottom_right.1 - self.top_left.1 + 1)
+    }
+}
+
+impl<T: Copy> Matrix<T> {
+    /// Applies `update` to every cell of `rectangle`, working on whole row slices. Panics if the rectangle
+    /// exceeds the matrix.
+    pub fn update_rectangle(&mut self, rectangle: &Rectangle, mut update: impl FnMut(&mut T)) {
+        self.assert_contains(rectangle);
+        for row in rectangle.rows() {
+            self.data[row][rectangle.columns()]
+                .iter_mut()
+                .for_each(&mut update);
+        }
+    }
+
+    /// Sets every cell of `rectangle` to `value`. Panics if the rectangle exceeds the matrix.
+    pub fn fill_rectangle(&mut self, rectangle: &Rectangle, value: T) {
+        self.assert_contains(rectangle);
+        for row in rectangle.rows() {
+            self.data[row][rectangle.columns()].fill(value);
+        }
+    }
+
+    pub fn toggle_rectangle(&mut self, rectangle: &Rectangle)
+    where
+        T: Not<Output = T>,
+    {
+        self.update_rectangle(rectangle, |cell| *cell = !*cell);
+    }
+
+    pub fn add_rectangle(&mut self, rectangle: &Rectangle, delta: T)
+    where
+        T: AddAssign,
+    {
+        self.update_rectangle(rectangle, |cell| *cell += delta);
+    }
+
+    fn assert_contains(&self, rectangle: &Rectangle) {
+        assert!(
+            rectangle.bottom_right.0 < self.rows && rectangle.bottom_right.1 < self.columns,
+            "{:?} exceeds the matrix of size {:?}",
+            rectangle,
+            self.size()
+        );
+    }
+}
+
+/// Sums over any rectangle of a `Matrix` in constant time after a linear precomputation.
+#[derive(Clone, Debug)]
+pub struct PrefixSums {
+    /// `sums[r][c]` is the sum of all cells above and left of `(r, c)`, exclusively.
+    sums: Vec<Vec<i64>>,
+}
+
+impl PrefixSums {
+    /// Prefix sums of `value` applied to every cell, e.g. `|on| on as i64` for a grid of lights.
+    pub fn new<T: Copy>(matrix: &Matrix<T>, value: impl Fn(T) -> i64) -> Self {
+        let (rows, columns) = matrix.size();
+        let mut sums = vec![vec![0; columns + 1]; rows + 1];
+        for row in 0..rows {
+            for col in 0..columns {
+                sums[row + 1][col + 1] =
+                    value(matrix.data[row][col]) + sums[row][col + 1] + sums[row + 1][col]
+                        - sums[row][col];
+            }
+        }
+        Self { sums }
+    }
+
+    pub fn sum(&self, rectangle: &Rectangle) -> i64 {
+        let (top, left) = rectangle.top_left;
+        let (bottom, right) = (rectangle.bottom_right.0 + 1, rectangle.bottom_right.1 + 1);
+        self.sums[bottom][right] - self.sums[top][right] - self.sums[bottom][left]
+            + self.sums[top][left]
+    }
+}
+
+/// Grid over a huge coordinate space that only stores one cell per block between rectangle boundaries.
+///
+/// All rectangles have to be known up front, since their edges determine the blocks.
+pub struct CompressedGrid<T> {
+    /// Start coordinates of the blocks, followed by the end of the last block.
+    row_bounds: Vec<usize>,
+    column_bounds: Vec<usize>,
+    blocks: Matrix<T>,
+}
+
+impl<T: Copy> CompressedGrid<T> {
+    /// Grid with blocks for the given rectangles, all set to `initial`. Everything outside of the rectangles'
+    /// bounding box is not represented.
+    pub fn new<'a>(rectangles: impl IntoIterator<Item = &'a Rectangle>, initial: T) -> Self {
+        let mut row_bounds = Vec::new();
+        let mut column_bounds = Vec::new();
+        for rectangle in rectangles {
+            row_bounds.extend([rectangle.top_left.0, rectangle.bottom_right.0 + 1]);
+            column_bounds.extend([rectangle.top_left.1, rectangle.bottom_right.1 + 1]);
+        }
+        assert!(!row_bounds.is_empty(), "At least one rectangle is required");
+        for bounds in [&mut row_bounds, &mut column_bounds] {
+            bounds.sort_unstable();
+            bounds.dedup();
+        }
+        let blocks = vec![vec![initial; column_bounds.len() - 1]; row_bounds.len() - 1];
+        Self {
+            row_bounds,
+            column_bounds,
+            blocks: Matrix::new(blocks),
+        }
+    }
+
+    /// The rectangle in block coordinates. Panics for rectangles the grid was not built with.
+    fn compress(&self, rectangle: &Rectangle) -> Rectangle {
+        let find = |bounds: &[usize], value: usize| {
+            bounds
+                .binary_search(&value)
+                .unwrap_or_else(|_| panic!("{:?} is not aligned to the grid", rectangle))
+        };
+        Rectangle {
+            top_left: (
+                find(&self.row_bounds, rectangle.top_left.0),
+                find(&self.column_bounds, rectangle.top_left.1),
+            ),
+            bottom_right: (
+                find(&self.row_bounds, rectangle.bottom_right.0 + 1) - 1,
+                find(&self.column_bounds, rectangle.bottom_right.1 + 1) - 1,
+            ),
+        }
+    }
+
+    pub fn update_rectangle(&mut self, rectangle: &Rectangle, update: impl FnMut(&mut T)) {
+        let compressed = self.compress(rectangle);
+        self.blocks.update_rectangle(&compressed, update);
+    }
+
+    pub fn fill_rectangle(&mut self, rectangle: &Rectangle, value: T) {
+        let compressed = self.compress(rectangle);
+        self.blocks.fill_rectangle(&compressed, value);
+    }
+
+    /// Sum of `value` over all cells, i.e. weighted by the area of each block.
+    pub fn sum(&self, value: impl Fn(T) -> i64) -> i64 {
+        let mut sum = 0;
+        for (row, heights) in self.row_bounds.windows(2).enumerate() {
+            for (col, widths) in self.column_bounds.windows(2).enumerate() {
+                let area = ((heights[1] - heights[0]) * (widths[1] - widths[0])) as i64;
+                sum += value(self.blocks.data[row][col]) * area;
+            }
+        }
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instructions() -> Vec<(&'static str, Rectangle)> {
+        vec![
+            ("on", Rectangle::new((0, 0), (7, 7))),
+            ("toggle", Rectangle::new((0, 4), (9, 0))),
+            ("off", Rectangle::new((3, 3), (4, 4))),
+            ("toggle", Rectangle::new((9, 9), (5, 5))),
+        ]
+    }
+
+    #[test]
+    fn test_matrix_rectangles() {
+        let mut lights = Matrix::filled(10, 10, false);
+        let mut brightness = Matrix::filled(10, 10, 0i64);
+        for (action, rectangle) in instructions() {
+            match action {
+                "on" => lights.fill_rectangle(&rectangle, true),
+                "off" => lights.fill_rectangle(&rectangle, false),
+                _ => lights.toggle_rectangle(&rectangle),
+            }
+            let delta = match action {
+                "on" => 1,
+                "off" => -1,
+                _ => 2,
+            };
+            brightness.add_rectangle(&rectangle, delta);
+        }
+        assert_eq!(
+            lights.data[0][..8],
+            [false, false, false, false, false, true, true, true]
+        );
+        assert_eq!(lights.data[9][..6], [true, true, true, true, true, true]);
+        let on = PrefixSums::new(&lights, |on| on as i64);
+        assert_eq!(
+            on.sum(&Rectangle::new((0, 0), (9, 9))),
+            64 - 40 + 10 - 9 + 16
+        );
+        assert_eq!(on.sum(&Rectangle::new((9, 0), (9, 9))), 6 + 4);
+        assert_eq!(on.sum(&Rectangle::new((4, 4), (4, 4))), 0);
+        let total = PrefixSums::new(&brightness, |value| value);
+        assert_eq!(
+            total.sum(&Rectangle::new((0, 0), (9, 9))),
+            64 + 2 * 50 - 4 + 2 * 25
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the matrix")]
+    fn test_rectangle_outside() {
+        Matrix::filled(2, 2, 0).add_rectangle(&Rectangle::new((0, 0), (1, 2)), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "exceeds the matrix")]
+    fn test_fill_outside() {
+        Matrix::filled(2, 2, 0).fill_rectangle(&Rectangle::new((2, 0), (2, 1)), 1);
+    }
+
+    #[test]
+    fn test_prefix_sums() {
+        let matrix = Matrix::new(vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+        let sums = PrefixSums::new(&matrix, |value| value);
+        for top in 0..3 {
+            for left in 0..3 {
+                for bottom in top..3 {
+                    for right in left..3 {
+                        let rectangle = Rectangle::new((top, left), (bottom, right));
+                        let expected: i64 = rectangle
+                            .rows()
+                            .flat_map(|row| matrix.data[row][rectangle.columns()].iter())
+                            .sum();
+                        assert_eq!(sums.sum(&rectangle), expected);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_compressed_grid() {
+        let instructions = instructions();
+        let mut grid =
+            CompressedGrid::new(instructions.iter().map(|(_, rectangle)| rectangle), 0i64);
+        let mut matrix = Matrix::filled(10, 10, 0i64);
+        for (action, rectangle) in &instructions {
+            let delta = if *action == "off" { -1 } else { 1 };
+            grid.update_rectangle(rectangle, |cell| *cell += delta);
+            matrix.add_rectangle(rectangle, delta);
+        }
+        assert_eq!(
+            grid.sum(|value| value),
+            PrefixSums::new(&matrix, |value| value).sum(&Rectangle::new((0, 0), (9, 9)))
+        );
+
+        let huge = [
+            Rectangle::new((0, 0), (999_999_999, 999_999_999)),
+            Rectangle::new((500_000_000, 0), (500_000_000, 999_999_999)),
+        ];
+        let mut grid = CompressedGrid::new(&huge, false);
+        grid.fill_rectangle(&huge[0], true);
+        grid.update_rectangle(&huge[1], |on| *on = !*on);
+        assert_eq!(grid.sum(|on| on as i64), 1_000_000_000 * 999_999_999);
+    }
+}
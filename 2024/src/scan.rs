@@ -0,0 +1,144 @@
+use std::{marker::PhantomData, num::ParseIntError, str::FromStr};
+
+/// Iterator over all unsigned integers in a line. Every non-digit, including `-`, acts as a separator.
+pub fn ints<T>(line: &str) -> Ints<'_, T>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    Ints::new(line, false)
+}
+
+/// Iterator over all integers in a line, taking a `-` directly in front of a digit as the sign.
+pub fn ints_signed<T>(line: &str) -> Ints<'_, T>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    Ints::new(line, true)
+}
+
+/// Primitive integers that can be picked from a line.
+pub trait Integer: Copy + FromStr<Err = ParseIntError> {
+    const SIGNED: bool;
+    const ZERO: Self;
+
+    /// Appends a decimal digit, i.e. `self * 10 + digit` or `self * 10 - digit` while reading a negative number.
+    /// Returns `None` on overflow.
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+}
+
+macro_rules! integer {
+    ($signed:expr => $($t:ty),*) => {
+        $(
+            impl Integer for $t {
+                const SIGNED: bool = $signed;
+                const ZERO: Self = 0;
+
+                fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                    let shifted = self.checked_mul(10)?;
+                    if negative {
+                        shifted.checked_sub(digit as Self)
+                    } else {
+                        shifted.checked_add(digit as Self)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+integer!(false => u8, u16, u32, u64, u128, usize);
+integer!(true => i8, i16, i32, i64, i128, isize);
+
+/// All integers of every line, read with `ints_signed` for signed types and with `ints` otherwise.
+pub fn lines_of_ints<T: Integer>(input: &str) -> Vec<Vec<T>> {
+    input
+        .lines()
+        .map(|line| Ints::new(line, T::SIGNED).collect())
+        .collect()
+}
+
+pub struct Ints<'a, T> {
+    line: &'a str,
+    position: usize,
+    signed: bool,
+    item: PhantomData<T>,
+}
+
+impl<'a, T> Ints<'a, T> {
+    fn new(line: &'a str, signed: bool) -> Self {
+        Self {
+            line,
+            position: 0,
+            signed,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<T> Iterator for Ints<'_, T>
+where
+    T: FromStr<Err = ParseIntError>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let (number, end) = next_int_token(self.line, self.position, self.signed)?;
+        self.position = end;
+        match number.parse() {
+            Ok(number) => Some(number),
+            Err(error) => panic!("Unable to read integer '{}': {}", number, error),
+        }
+    }
+}
+
+fn next_int_token(line: &str, position: usize, signed: bool) -> Option<(&str, usize)> {
+    let bytes = line.as_bytes();
+    let digits_start = position + bytes[position..].iter().position(u8::is_ascii_digit)?;
+    let digits_end = digits_start
+        + bytes[digits_start..]
+            .iter()
+            .position(|b| !b.is_ascii_digit())
+            .unwrap_or(bytes.len() - digits_start);
+    let start = if signed && digits_start > 0 && bytes[digits_start - 1] == b'-' {
+        digits_start - 1
+    } else {
+        digits_start
+    };
+    Some((&line[start..digits_end], digits_end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ints() {
+        let line = "Sensor at x=-2, y=15: 3-7 and 42";
+        assert_eq!(ints::<u32>(line).collect::<Vec<_>>(), [2, 15, 3, 7, 42]);
+        assert_eq!(
+            ints_signed::<i64>(line).collect::<Vec<_>>(),
+            [-2, 15, 3, -7, 42]
+        );
+        assert_eq!(ints::<u8>("no numbers").count(), 0);
+        assert_eq!(ints::<usize>("1").collect::<Vec<_>>(), [1]);
+    }
+
+    #[test]
+    fn test_lines_of_ints() {
+        assert_eq!(
+            lines_of_ints::<u64>("7 6 4\n1 2\n\n8"),
+            vec![vec![7, 6, 4], vec![1, 2], vec![], vec![8]]
+        );
+        assert_eq!(
+            lines_of_ints::<i64>("-3 4\n5 -6"),
+            vec![vec![-3, 4], vec![5, -6]]
+        );
+        assert_eq!(lines_of_ints::<u8>("-3 4"), vec![vec![3, 4]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_overflow() {
+        ints::<u8>("300").for_each(drop);
+    }
+}
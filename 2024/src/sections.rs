@@ -0,0 +1,286 @@
+use crate::{scan::Integer, InputError};
+
+/// A block of consecutive non-empty lines together with the line number of its first line in the whole input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Section<'a> {
+    pub text: &'a str,
+    pub first_line: usize,
+}
+
+impl<'a> Section<'a> {
+    /// Lines of the section paired with their 1-based line number in the whole input.
+    pub fn lines(&self) -> impl Iterator<Item = (usize, &'a str)> {
+        let first_line = self.first_line;
+        self.text
+            .lines()
+            .enumerate()
+            .map(move |(index, line)| (first_line + index, line))
+    }
+}
+
+/// Splits the input at blank lines. Several blank lines in a row count as a single separator.
+pub fn split_sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    let mut current: Option<(usize, usize, usize)> = None;
+    for (index, line) in input.lines().enumerate() {
+        let start = line.as_ptr() as usize - input.as_ptr() as usize;
+        if line.trim().is_empty() {
+            if let Some((first_line, from, to)) = current.take() {
+                sections.push(Section {
+                    text: &input[from..to],
+                    first_line,
+                });
+            }
+        } else {
+            let (first_line, from, _) = current.unwrap_or((index + 1, start, start));
+            current = Some((first_line, from, start + line.len()));
+        }
+    }
+    if let Some((first_line, from, to)) = current {
+        sections.push(Section {
+            text: &input[from..to],
+            first_line,
+        });
+    }
+    sections
+}
+
+/// Fails unless `input` consists of exactly `expected` sections.
+pub fn expect_section_count(
+    input: &str,
+    sections: &[Section],
+    expected: usize,
+) -> Result<(), InputError> {
+    let message = || {
+        format!(
+            "Expected {} sections but found {}",
+            expected,
+            sections.len()
+        )
+    };
+    if let Some(surplus) = sections.get(expected) {
+        let line = surplus.text.lines().next().unwrap_or_default();
+        return Err(InputError::new(surplus.first_line, line, 0, message()));
+    }
+    if sections.len() < expected {
+        let (line_number, line) = input
+            .lines()
+            .enumerate()
+            .last()
+            .map_or((1, ""), |(index, line)| (index + 1, line));
+        return Err(InputError::new(line_number, line, line.len(), message()));
+    }
+    Ok(())
+}
+
+/// Types that can be read from a whole section.
+pub trait FromSection: Sized {
+    fn from_section(section: Section, separator: &str) -> Result<Self, InputError>;
+}
+
+/// Types that can be read from a single line whose values are separated by `separator`.
+pub trait FromLine: Sized {
+    fn from_line(line: &str, separator: &str) -> Result<Self, String>;
+}
+
+impl<T: FromLine> FromSection for Vec<T> {
+    fn from_section(section: Section, separator: &str) -> Result<Self, InputError> {
+        section
+            .lines()
+            .map(|(line_number, line)| {
+                T::from_line(line, separator)
+                    .map_err(|message| InputError::new(line_number, line, 0, message))
+            })
+            .collect()
+    }
+}
+
+impl FromSection for String {
+    fn from_section(section: Section, _separator: &str) -> Result<Self, InputError> {
+        Ok(section.text.to_string())
+    }
+}
+
+impl FromLine for String {
+    fn from_line(line: &str, _separator: &str) -> Result<Self, String> {
+        Ok(line.to_string())
+    }
+}
+
+macro_rules! integer {
+    ($($t:ty),*) => {
+        $(
+            impl FromLine for $t {
+                fn from_line(line: &str, _separator: &str) -> Result<Self, String> {
+                    parse(line)
+                }
+            }
+        )*
+    };
+}
+
+integer!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl<T: Integer> FromLine for Vec<T> {
+    fn from_line(line: &str, separator: &str) -> Result<Self, String> {
+        line.split(separator).map(parse).collect()
+    }
+}
+
+fn fields<'a, const N: usize>(line: &'a str, separator: &str) -> Result<[&'a str; N], String> {
+    let mut fields = [""; N];
+    let mut count = 0;
+    for field in line.split(separator) {
+        if let Some(slot) = fields.get_mut(count) {
+            *slot = field;
+        }
+        count += 1;
+    }
+    if count == N {
+        Ok(fields)
+    } else {
+        Err(format!(
+            "Expected {} integers separated by '{}' but found {}",
+            N, separator, count
+        ))
+    }
+}
+
+fn parse<T: Integer>(number: &str) -> Result<T, String> {
+    number
+        .parse()
+        .map_err(|error| format!("Unable to read integer '{}': {}", number, error))
+}
+
+macro_rules! integer_tuple {
+    ($($t:ident $v:ident),*) => {
+        impl<$($t: Integer),*> FromLine for ($($t,)*) {
+            fn from_line(line: &str, separator: &str) -> Result<Self, String> {
+                let [$($v),*] = fields(line, separator)?;
+                Ok(($(parse::<$t>($v)?,)*))
+            }
+        }
+    };
+}
+
+integer_tuple!(A a, B b);
+integer_tuple!(A a, B b, C c);
+integer_tuple!(A a, B b, C c, D d);
+
+/// Splits blank-line separated input into sections and reads each one into the given type.
+///
+/// Binds one variable per section and returns early with an `InputError` if a section cannot be read or if the
+/// number of sections differs. Every binding names the separator between the values of a line, e.g.
+/// `sections!(input => rules: Vec<(u32, u32)> = "|", mut updates: Vec<Vec<u32>> = ",");`. `String` sections ignore
+/// it.
+#[macro_export]
+macro_rules! sections {
+    ($input:expr => $($bindings:tt)+) => {
+        let input: &str = $input;
+        let sections = $crate::sections::split_sections(input);
+        $crate::sections::expect_section_count(input, &sections, $crate::sections!(@count $($bindings)+))?;
+        let mut sections = sections.into_iter();
+        $crate::sections!(@bind sections; $($bindings)+);
+    };
+
+    (@count mut $name:ident: $t:ty = $separator:literal $(, $($rest:tt)*)?) => {
+        1 $(+ $crate::sections!(@count $($rest)*))?
+    };
+    (@count $name:ident: $t:ty = $separator:literal $(, $($rest:tt)*)?) => {
+        1 $(+ $crate::sections!(@count $($rest)*))?
+    };
+    (@count $(mut)? $name:ident: $t:ty $(, $($rest:tt)*)?) => {
+        compile_error!(concat!(
+            "section `", stringify!($name), "` needs a separator, e.g. `", stringify!($name), ": ", stringify!($t),
+            " = \" \"`"
+        ))
+    };
+    (@count) => { 0 };
+
+    (@bind $sections:ident; mut $name:ident: $t:ty = $separator:literal $(, $($rest:tt)*)?) => {
+        let mut $name: $t = $crate::sections::FromSection::from_section($sections.next().unwrap(), $separator)?;
+        $($crate::sections!(@bind $sections; $($rest)*);)?
+    };
+    (@bind $sections:ident; $name:ident: $t:ty = $separator:literal $(, $($rest:tt)*)?) => {
+        let $name: $t = $crate::sections::FromSection::from_section($sections.next().unwrap(), $separator)?;
+        $($crate::sections!(@bind $sections; $($rest)*);)?
+    };
+    (@bind $sections:ident;) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Manual = (Vec<(u32, u32)>, Vec<Vec<u32>>);
+
+    fn read(input: &str) -> Result<Manual, InputError> {
+        sections!(input => rules: Vec<(u32, u32)> = "|", mut updates: Vec<Vec<u32>> = ",",);
+        updates.sort_by_key(Vec::len);
+        Ok((rules, updates))
+    }
+
+    #[test]
+    fn test_split_sections() {
+        let sections = split_sections("a\nb\n\n\nc\n");
+        assert_eq!(
+            sections,
+            [
+                Section {
+                    text: "a\nb",
+                    first_line: 1
+                },
+                Section {
+                    text: "c",
+                    first_line: 5
+                }
+            ]
+        );
+        assert_eq!(split_sections("\n\n"), []);
+    }
+
+    #[test]
+    fn test_sections() {
+        let (rules, updates) = read("47|53\n97|13\n\n75,47,61\n97,61\n").unwrap();
+        assert_eq!(rules, [(47, 53), (97, 13)]);
+        assert_eq!(updates, [vec![97, 61], vec![75, 47, 61]]);
+    }
+
+    #[test]
+    fn test_section_errors() {
+        let error = read("47|53\n\n75,47\n\n1").unwrap_err();
+        assert_eq!((error.line_number, error.column), (5, 1));
+        assert_eq!(error.message, "Expected 2 sections but found 3");
+
+        let error = read("47|53\n97|13").unwrap_err();
+        assert_eq!((error.line_number, error.column), (2, 6));
+
+        let error = read("47|53\n97\n\n75,47").unwrap_err();
+        assert_eq!(error.line_number, 2);
+        assert_eq!(
+            error.message,
+            "Expected 2 integers separated by '|' but found 1"
+        );
+
+        let error = read("47|53\n1,2\n\n75,47").unwrap_err();
+        assert_eq!(error.line_number, 2);
+        assert_eq!(
+            error.message,
+            "Expected 2 integers separated by '|' but found 1"
+        );
+
+        let error = read("47|53\n3 4\n\n75,47").unwrap_err();
+        assert_eq!(error.line_number, 2);
+
+        let error = read("47|53\n\n75,47\n97, 61").unwrap_err();
+        assert_eq!(error.line_number, 4);
+        assert!(error.message.starts_with("Unable to read integer ' 61'"));
+    }
+
+    #[test]
+    fn test_default_separator() {
+        assert_eq!(Vec::<i32>::from_line("3 -4 5", " "), Ok(vec![3, -4, 5]));
+        assert_eq!(<(u8, u8)>::from_line("3  4", " ").map_err(|_| ()), Err(()));
+        assert_eq!(u64::from_line("42", " "), Ok(42));
+    }
+}
@@ -39,6 +39,12 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// With `parse = parse_input`, the input is parsed once by `parse_input(&str) -> Result<T, E>` and both parts
+/// receive `&T`. Parsing is timed separately, and the parts are skipped if it fails.
+///
+/// With `solve = solve`, a single function computes both parts and returns them as a tuple. It receives the input
+/// or, combined with `parse`, the parsed data.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
@@ -50,6 +56,37 @@ macro_rules! solution {
     ($day:expr, 2) => {
         $crate::solution!(@impl $day, [part_two, 2]);
     };
+    ($day:expr, solve = $solve:expr) => {
+        /// The current day.
+        const DAY: $crate::template::Day = $crate::day!($day);
+
+        fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::read_file("inputs", DAY);
+            run_both($solve, &input, DAY);
+        }
+    };
+    ($day:expr, parse = $parse:expr, solve = $solve:expr) => {
+        /// The current day.
+        const DAY: $crate::template::Day = $crate::day!($day);
+
+        fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::read_file("inputs", DAY);
+            if let Some(data) = run_parse($parse, &input) {
+                run_both($solve, &data, DAY);
+            }
+        }
+    };
+    ($day:expr, parse = $parse:expr) => {
+        $crate::solution!(@parse $day, $parse, [part_one, 1] [part_two, 2]);
+    };
+    ($day:expr, parse = $parse:expr, 1) => {
+        $crate::solution!(@parse $day, $parse, [part_one, 1]);
+    };
+    ($day:expr, parse = $parse:expr, 2) => {
+        $crate::solution!(@parse $day, $parse, [part_two, 2]);
+    };
 
     (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
         /// The current day.
@@ -61,4 +98,17 @@ macro_rules! solution {
             $( run_part($func, &input, DAY, $part); )*
         }
     };
+
+    (@parse $day:expr, $parse:expr, $( [$func:expr, $part:expr] )*) => {
+        /// The current day.
+        const DAY: $crate::template::Day = $crate::day!($day);
+
+        fn main() {
+            use $crate::template::runner::*;
+            let input = $crate::template::read_file("inputs", DAY);
+            if let Some(data) = run_parse($parse, &input) {
+                $( run_part($func, &data, DAY, $part); )*
+            }
+        }
+    };
 }
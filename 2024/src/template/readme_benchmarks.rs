@@ -65,12 +65,19 @@ fn construct_table(prefix: &str, timings: Timings, total_millis: f64) -> String
 
     for timing in timings.data {
         let path = get_path_for_bin(timing.day);
+        let parts = match timing.both_parts {
+            Some(both_parts) => format!("`{both_parts}` (both parts) | |"),
+            None => format!(
+                "`{}` | `{}` |",
+                timing.part_1.unwrap_or_else(|| "-".into()),
+                timing.part_2.unwrap_or_else(|| "-".into())
+            ),
+        };
         lines.push(format!(
-            "| [Day {}]({}) | `{}` | `{}` |",
+            "| [Day {}]({}) | {}",
             timing.day.into_inner(),
             path,
-            timing.part_1.unwrap_or_else(|| "-".into()),
-            timing.part_2.unwrap_or_else(|| "-".into())
+            parts
         ));
     }
 
@@ -131,8 +131,10 @@ pub mod child_commands {
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
         let mut timings = super::Timing {
             day,
+            parse: None,
             part_1: None,
             part_2: None,
+            both_parts: None,
             total_nanos: 0_f64,
         };
 
@@ -152,10 +154,14 @@ pub mod child_commands {
                 Some((part, timing_str, nanos))
             })
             .for_each(|(part, timing_str, nanos)| {
-                if part.contains("Part 1") {
+                if part.contains("Parse") {
+                    timings.parse = Some(timing_str.into());
+                } else if part.contains("Part 1") {
                     timings.part_1 = Some(timing_str.into());
                 } else if part.contains("Part 2") {
                     timings.part_2 = Some(timing_str.into());
+                } else if part.contains("Both parts") {
+                    timings.both_parts = Some(timing_str.into());
                 }
 
                 timings.total_nanos += nanos;
@@ -174,7 +180,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -189,3 +195,21 @@ pub mod child_commands {
         Some((str_timing, parsed_timing))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::child_commands::parse_exec_time;
+
+    #[test]
+    fn test_parse_exec_time() {
+        let output = [
+            "Parse: ✔ (4.2µs @ 10000 samples)".to_string(),
+            "Both parts: (250.0ns @ 10000 samples)".to_string(),
+        ];
+        let timing = parse_exec_time(&output, crate::day!(5));
+        assert_eq!(timing.parse.as_deref(), Some("4.2µs"));
+        assert_eq!(timing.both_parts.as_deref(), Some("250.0ns"));
+        assert_eq!((timing.part_1, timing.part_2), (None, None));
+        assert_eq!(timing.total_nanos, 4450.0);
+    }
+}
@@ -6,22 +6,116 @@ use std::process::Output;
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::answer::Answer;
+use crate::math::Number;
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Return types accepted from solution functions: a plain `Option` or a `Result` whose error is reported as failure.
+pub trait PartResult {
+    type Answer: Display;
+
+    fn into_answer(self) -> Result<Option<Self::Answer>, String>;
+}
+
+impl<T: Display> PartResult for Option<T> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<Option<T>, String> {
+        Ok(self)
+    }
+}
+
+impl<T: Display, E: Display> PartResult for Result<T, E> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<Option<T>, String> {
+        self.map(Some).map_err(|error| error.to_string())
+    }
+}
+
+/// Overflowed answers are reported as failures, so that they are never submitted.
+impl<T: Number + Display> PartResult for Answer<T> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<Option<T>, String> {
+        match self.value() {
+            Some(value) => Ok(Some(value)),
+            None => Err(format!(
+                "The answer overflowed {}",
+                std::any::type_name::<T>()
+            )),
+        }
+    }
+}
+
+impl<T: Number + Display, E: Display> PartResult for Result<Answer<T>, E> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<Option<T>, String> {
+        self.map_err(|error| error.to_string())?.into_answer()
+    }
+}
+
+pub fn run_part<I: Clone, R: PartResult>(func: impl Fn(I) -> R, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    let (result, duration, samples) = run_timed(
+        |input| func(input).into_answer(),
+        input,
+        |result| print_result(result, &part_str, ""),
+    );
 
     print_result(&result, &part_str, &format_duration(&duration, samples));
 
-    if let Some(result) = result {
+    if let Ok(Some(result)) = result {
         submit_result(result, day, part);
     }
 }
 
+/// Runs a `solve` function that computes both parts in one go. Both answers are printed and submitted like separate
+/// parts, but the benchmark covers the single execution.
+pub fn run_both<I: Clone, A: PartResult, B: PartResult>(
+    func: impl Fn(I) -> (A, B),
+    input: I,
+    day: Day,
+) {
+    let (results, duration, samples) = run_timed(
+        |input| {
+            let (part_one, part_two) = func(input);
+            (part_one.into_answer(), part_two.into_answer())
+        },
+        input,
+        |results| {
+            print_result(&results.0, "Part 1", " (both parts)");
+            print_result(&results.1, "Part 2", " (both parts)");
+            print!("Both parts:");
+        },
+    );
+
+    print!("\r");
+    println!("Both parts:{}", format_duration(&duration, samples));
+
+    if let Ok(Some(result)) = results.0 {
+        submit_result(result, day, 1);
+    }
+    if let Ok(Some(result)) = results.1 {
+        submit_result(result, day, 2);
+    }
+}
+
+/// Runs the `parse` function of a solution and reports its time separately from the parts. Returns `None` after
+/// reporting the error if the input cannot be parsed.
+pub fn run_parse<T, E: Display>(func: impl Fn(&str) -> Result<T, E>, input: &str) -> Option<T> {
+    let (result, duration, samples) = run_timed(func, input, |result| {
+        print_parse_result(result.as_ref().err(), "");
+    });
+
+    print_parse_result(result.as_ref().err(), &format_duration(&duration, samples));
+
+    result.ok()
+}
+
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
@@ -89,11 +183,11 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result<T: Display>(result: &Result<Option<T>, String>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
-        Some(result) => {
+        Ok(Some(result)) => {
             if result.to_string().contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
@@ -113,7 +207,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        Ok(None) => {
             if is_intermediate_result {
                 print!("{part}: ✖");
             } else {
@@ -121,6 +215,33 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 println!("{part}: ✖             ");
             }
         }
+        Err(error) => {
+            if is_intermediate_result {
+                print!("{part}: ✖");
+            } else {
+                print!("\r");
+                println!("{part}: ✖ {ANSI_ITALIC}failed{ANSI_RESET}");
+                eprintln!("{error}");
+            }
+        }
+    }
+}
+
+fn print_parse_result<E: Display>(error: Option<&E>, duration_str: &str) {
+    let is_intermediate_result = duration_str.is_empty();
+
+    match error {
+        None if is_intermediate_result => print!("Parse: ✔"),
+        None => {
+            print!("\r");
+            println!("Parse: ✔{duration_str}");
+        }
+        Some(_) if is_intermediate_result => print!("Parse: ✖"),
+        Some(error) => {
+            print!("\r");
+            println!("Parse: ✖ {ANSI_ITALIC}failed{ANSI_RESET}");
+            eprintln!("{error}");
+        }
     }
 }
 
@@ -162,3 +283,25 @@ fn submit_result<T: Display>(
     println!("Submitting result via aoc-cli...");
     Some(aoc_cli::submit(day, part, &result.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_answer() {
+        assert_eq!(Some(3).into_answer(), Ok(Some(3)));
+        assert_eq!(
+            Err::<u32, _>("bad input").into_answer(),
+            Err("bad input".to_string())
+        );
+        assert_eq!(
+            (Answer::new(u16::MAX) + 1).into_answer(),
+            Err("The answer overflowed u16".to_string())
+        );
+        assert_eq!(
+            Ok::<_, String>(Answer::new(7u16)).into_answer(),
+            Ok(Some(7))
+        );
+    }
+}
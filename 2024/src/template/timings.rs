@@ -9,8 +9,12 @@ static TIMINGS_FILE_PATH: &str = "./data/timings.json";
 #[derive(Clone, Debug)]
 pub struct Timing {
     pub day: Day,
+    /// Time of the separate `parse` function, if the solution has one.
+    pub parse: Option<String>,
     pub part_1: Option<String>,
     pub part_2: Option<String>,
+    /// Time of a shared `solve` function that computes both parts in one run.
+    pub both_parts: Option<String>,
     pub total_nanos: f64,
 }
 
@@ -51,7 +55,7 @@ impl Timings {
             }
         }
 
-        data.sort_unstable_by(|a, b| a.day.cmp(&b.day));
+        data.sort_unstable_by_key(|a| a.day);
         Timings { data }
     }
 
@@ -61,9 +65,9 @@ impl Timings {
     }
 
     pub fn is_day_complete(&self, day: Day) -> bool {
-        self.data
-            .iter()
-            .any(|t| t.day == day && t.part_1.is_some() && t.part_2.is_some())
+        self.data.iter().any(|t| {
+            t.day == day && (t.part_1.is_some() && t.part_2.is_some() || t.both_parts.is_some())
+        })
     }
 }
 
@@ -114,8 +118,18 @@ impl From<&Timing> for JsonValue {
         map.insert("day".into(), JsonValue::String(value.day.to_string()));
         map.insert("total_nanos".into(), JsonValue::Number(value.total_nanos));
 
+        let parse = value.parse.clone().map(JsonValue::String);
         let part_1 = value.part_1.clone().map(JsonValue::String);
         let part_2 = value.part_2.clone().map(JsonValue::String);
+        let both_parts = value.both_parts.clone().map(JsonValue::String);
+
+        map.insert(
+            "parse".into(),
+            match parse {
+                Some(x) => x,
+                None => JsonValue::Null,
+            },
+        );
 
         map.insert(
             "part_1".into(),
@@ -133,6 +147,14 @@ impl From<&Timing> for JsonValue {
             },
         );
 
+        map.insert(
+            "both_parts".into(),
+            match both_parts {
+                Some(x) => x,
+                None => JsonValue::Null,
+            },
+        );
+
         JsonValue::Object(map)
     }
 }
@@ -151,6 +173,18 @@ impl TryFrom<&JsonValue> for Timing {
             .and_then(|day| Day::from_str(day).ok())
             .ok_or("Expected timing.day to be a Day struct.")?;
 
+        // Timings written before `parse` was introduced lack the key.
+        let parse = json
+            .get("parse")
+            .map_or(Some(None), |v| {
+                if v.is_null() {
+                    Some(None)
+                } else {
+                    v.get::<String>().map(Some)
+                }
+            })
+            .ok_or("Expected timing.parse to be null or string.")?;
+
         let part_1 = json
             .get("part_1")
             .map(|v| if v.is_null() { None } else { v.get::<String>() })
@@ -161,6 +195,18 @@ impl TryFrom<&JsonValue> for Timing {
             .map(|v| if v.is_null() { None } else { v.get::<String>() })
             .ok_or("Expected timing.part_2 to be null or string.")?;
 
+        // Timings written before `both_parts` was introduced lack the key.
+        let both_parts = json
+            .get("both_parts")
+            .map_or(Some(None), |v| {
+                if v.is_null() {
+                    Some(None)
+                } else {
+                    v.get::<String>().map(Some)
+                }
+            })
+            .ok_or("Expected timing.both_parts to be null or string.")?;
+
         let total_nanos = json
             .get("total_nanos")
             .and_then(|v| v.get::<f64>().copied())
@@ -168,8 +214,10 @@ impl TryFrom<&JsonValue> for Timing {
 
         Ok(Timing {
             day,
+            parse: parse.cloned(),
             part_1: part_1.cloned(),
             part_2: part_2.cloned(),
+            both_parts: both_parts.cloned(),
             total_nanos,
         })
     }
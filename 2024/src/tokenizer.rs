@@ -0,0 +1,213 @@
+//! Tokenizer for instructions of the shape `name(argument,argument)` hidden in otherwise arbitrary text.
+
+use crate::scan::Integer;
+
+pub const MAX_ARGUMENTS: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Argument {
+    /// Unsigned decimal number with at least one and at most `max_digits` digits.
+    Number { max_digits: usize },
+    /// Like `Number`, but with an optional leading `-`.
+    SignedNumber { max_digits: usize },
+}
+
+/// An instruction the tokenizer recognizes, identified by its `kind` in the produced tokens.
+#[derive(Clone, Copy, Debug)]
+pub struct Signature<K: 'static> {
+    pub kind: K,
+    pub name: &'static str,
+    pub arguments: &'static [Argument],
+}
+
+impl<K> Signature<K> {
+    pub const fn new(kind: K, name: &'static str, arguments: &'static [Argument]) -> Self {
+        assert!(arguments.len() <= MAX_ARGUMENTS);
+        Self {
+            kind,
+            name,
+            arguments,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Token<'a, K> {
+    pub kind: K,
+    /// Byte offset of the instruction in the input.
+    pub offset: usize,
+    /// The complete instruction, e.g. `mul(2,4)`.
+    pub text: &'a str,
+    values: [i64; MAX_ARGUMENTS],
+    count: usize,
+}
+
+impl<K> Token<'_, K> {
+    pub fn arguments(&self) -> &[i64] {
+        &self.values[..self.count]
+    }
+}
+
+pub struct Tokenizer<K: 'static> {
+    signatures: &'static [Signature<K>],
+}
+
+impl<K: Copy> Tokenizer<K> {
+    /// Creates a tokenizer for the given signatures. If several signatures match at the same position,
+    /// the first one wins.
+    pub const fn new(signatures: &'static [Signature<K>]) -> Self {
+        Self { signatures }
+    }
+
+    /// Finds all non-overlapping instructions from left to right in a single pass over `input`.
+    pub fn tokenize<'a>(&self, input: &'a str) -> impl Iterator<Item = Token<'a, K>> + 'a {
+        let signatures = self.signatures;
+        let bytes = input.as_bytes();
+        let mut position = 0;
+        std::iter::from_fn(move || {
+            while position < bytes.len() {
+                let start = position;
+                let token = signatures
+                    .iter()
+                    .find_map(|signature| Self::match_signature(signature, bytes, start));
+                match token {
+                    Some((kind, values, count, end)) => {
+                        position = end;
+                        return Some(Token {
+                            kind,
+                            offset: start,
+                            text: &input[start..end],
+                            values,
+                            count,
+                        });
+                    }
+                    None => position += 1,
+                }
+            }
+            None
+        })
+    }
+
+    fn match_signature(
+        signature: &Signature<K>,
+        bytes: &[u8],
+        start: usize,
+    ) -> Option<(K, [i64; MAX_ARGUMENTS], usize, usize)> {
+        let mut position = start;
+        expect(bytes, &mut position, signature.name.as_bytes())?;
+        expect(bytes, &mut position, b"(")?;
+        let mut values = [0; MAX_ARGUMENTS];
+        for (index, argument) in signature.arguments.iter().enumerate() {
+            if index > 0 {
+                expect(bytes, &mut position, b",")?;
+            }
+            let (max_digits, signed) = match argument {
+                Argument::Number { max_digits } => (*max_digits, false),
+                Argument::SignedNumber { max_digits } => (*max_digits, true),
+            };
+            let negative = signed && bytes.get(position) == Some(&b'-');
+            position += negative as usize;
+            let digits = bytes[position..]
+                .iter()
+                .take(max_digits)
+                .take_while(|byte| byte.is_ascii_digit())
+                .count();
+            if digits == 0 {
+                return None;
+            }
+            // Numbers that do not fit into an `i64` are no valid arguments.
+            let value = bytes[position..position + digits]
+                .iter()
+                .try_fold(0i64, |value, digit| {
+                    value.push_digit(digit - b'0', negative)
+                })?;
+            values[index] = value;
+            position += digits;
+        }
+        expect(bytes, &mut position, b")")?;
+        Some((signature.kind, values, signature.arguments.len(), position))
+    }
+}
+
+fn expect(bytes: &[u8], position: &mut usize, expected: &[u8]) -> Option<()> {
+    bytes[*position..].starts_with(expected).then(|| {
+        *position += expected.len();
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Instruction {
+        Do,
+        Dont,
+        Mul,
+        Shift,
+    }
+
+    const TOKENIZER: Tokenizer<Instruction> = Tokenizer::new(&[
+        Signature::new(Instruction::Do, "do", &[]),
+        Signature::new(Instruction::Dont, "don't", &[]),
+        Signature::new(
+            Instruction::Mul,
+            "mul",
+            &[
+                Argument::Number { max_digits: 3 },
+                Argument::Number { max_digits: 3 },
+            ],
+        ),
+        Signature::new(
+            Instruction::Shift,
+            "shift",
+            &[Argument::SignedNumber { max_digits: 2 }],
+        ),
+    ]);
+
+    #[test]
+    fn test_tokenize() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?shift(-12)";
+        let tokens: Vec<_> = TOKENIZER.tokenize(input).collect();
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| (token.kind, token.arguments()))
+                .collect::<Vec<_>>(),
+            [
+                (Instruction::Mul, &[2, 4][..]),
+                (Instruction::Dont, &[]),
+                (Instruction::Mul, &[5, 5]),
+                (Instruction::Mul, &[11, 8]),
+                (Instruction::Do, &[]),
+                (Instruction::Shift, &[-12]),
+            ]
+        );
+        assert_eq!((tokens[0].offset, tokens[0].text), (1, "mul(2,4)"));
+        assert_eq!((tokens[4].offset, tokens[4].text), (59, "do()"));
+    }
+
+    #[test]
+    fn test_digit_limits() {
+        let tokens: Vec<_> = TOKENIZER
+            .tokenize("mul(1234,5)mul(123,4)mul(,1)shift(-)shift(100)")
+            .collect();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].arguments(), [123, 4]);
+
+        const WIDE: Tokenizer<Instruction> = Tokenizer::new(&[Signature::new(
+            Instruction::Shift,
+            "shift",
+            &[Argument::SignedNumber { max_digits: 25 }],
+        )]);
+        let tokens: Vec<_> = WIDE
+            .tokenize(concat!(
+                "shift(99999999999999999999)shift(-9223372036854775807)",
+                "shift(-9223372036854775808)shift(-9223372036854775809)",
+                "shift(9223372036854775807)shift(9223372036854775808)"
+            ))
+            .collect();
+        let arguments: Vec<_> = tokens.iter().map(|token| token.arguments()[0]).collect();
+        assert_eq!(arguments, [-i64::MAX, i64::MIN, i64::MAX]);
+    }
+}
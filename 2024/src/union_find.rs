@@ -0,0 +1,277 @@
+//! Disjoint sets for clustering and connectivity, over plain indices or arbitrary keys.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Disjoint sets over the indices `0..len()` with path compression and union by size.
+#[derive(Clone, Debug)]
+pub struct UnionFind {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+    set_count: usize,
+}
+
+impl UnionFind {
+    /// `len` singleton sets.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parents: (0..len).collect(),
+            sizes: vec![1; len],
+            set_count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parents.is_empty()
+    }
+
+    /// Adds a new singleton set and returns its element.
+    pub fn push(&mut self) -> usize {
+        self.parents.push(self.parents.len());
+        self.sizes.push(1);
+        self.set_count += 1;
+        self.parents.len() - 1
+    }
+
+    /// The representative of the set containing `element`.
+    pub fn find(&mut self, element: usize) -> usize {
+        let mut root = element;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut current = element;
+        while self.parents[current] != root {
+            current = std::mem::replace(&mut self.parents[current], root);
+        }
+        root
+    }
+
+    /// Merges the sets of `a` and `b`. Returns whether they were separate before.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (larger, smaller) = if self.sizes[a] >= self.sizes[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parents[smaller] = larger;
+        self.sizes[larger] += self.sizes[smaller];
+        self.set_count -= 1;
+        true
+    }
+
+    pub fn connected(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn set_count(&self) -> usize {
+        self.set_count
+    }
+
+    /// Size of the set containing `element`.
+    pub fn size_of(&mut self, element: usize) -> usize {
+        let root = self.find(element);
+        self.sizes[root]
+    }
+
+    /// Sizes of all sets, largest first.
+    pub fn set_sizes(&self) -> Vec<usize> {
+        let mut sizes: Vec<usize> = (0..self.len())
+            .filter(|element| self.parents[*element] == *element)
+            .map(|root| self.sizes[root])
+            .collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+        sizes
+    }
+
+    /// All sets with their elements in ascending order, ordered by their smallest element.
+    pub fn sets(&mut self) -> Vec<Vec<usize>> {
+        let mut indices = HashMap::new();
+        let mut sets: Vec<Vec<usize>> = Vec::with_capacity(self.set_count);
+        for element in 0..self.len() {
+            let root = self.find(element);
+            let index = *indices.entry(root).or_insert_with(|| {
+                sets.push(Vec::new());
+                sets.len() - 1
+            });
+            sets[index].push(element);
+        }
+        sets
+    }
+}
+
+/// `UnionFind` over hashable keys such as `Coordinate`s, which are added on first use.
+#[derive(Clone, Debug)]
+pub struct KeyedUnionFind<K> {
+    keys: Vec<K>,
+    indices: HashMap<K, usize>,
+    sets: UnionFind,
+}
+
+impl<K: Clone + Eq + Hash> Default for KeyedUnionFind<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Clone + Eq + Hash> FromIterator<K> for KeyedUnionFind<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(keys: I) -> Self {
+        let mut sets = Self::new();
+        for key in keys {
+            sets.insert(key);
+        }
+        sets
+    }
+}
+
+impl<K: Clone + Eq + Hash> KeyedUnionFind<K> {
+    pub fn new() -> Self {
+        Self {
+            keys: Vec::new(),
+            indices: HashMap::new(),
+            sets: UnionFind::new(0),
+        }
+    }
+
+    /// Adds `key` as a singleton set unless it is known already. Returns its index.
+    pub fn insert(&mut self, key: K) -> usize {
+        if let Some(index) = self.indices.get(&key) {
+            return *index;
+        }
+        let index = self.sets.push();
+        self.keys.push(key.clone());
+        self.indices.insert(key, index);
+        index
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    /// The representative key of the set containing `key`, if `key` is known.
+    pub fn find(&mut self, key: &K) -> Option<&K> {
+        let index = *self.indices.get(key)?;
+        let root = self.sets.find(index);
+        Some(&self.keys[root])
+    }
+
+    /// Merges the sets of `a` and `b`, adding unknown keys first. Returns whether they were separate before.
+    pub fn union(&mut self, a: K, b: K) -> bool {
+        let (a, b) = (self.insert(a), self.insert(b));
+        self.sets.union(a, b)
+    }
+
+    pub fn connected(&mut self, a: &K, b: &K) -> bool {
+        match (self.indices.get(a), self.indices.get(b)) {
+            (Some(a), Some(b)) => self.sets.connected(*a, *b),
+            _ => false,
+        }
+    }
+
+    pub fn set_count(&self) -> usize {
+        self.sets.set_count()
+    }
+
+    /// Size of the set containing `key`, 0 for unknown keys.
+    pub fn size_of(&mut self, key: &K) -> usize {
+        match self.indices.get(key) {
+            Some(index) => self.sets.size_of(*index),
+            None => 0,
+        }
+    }
+
+    pub fn set_sizes(&self) -> Vec<usize> {
+        self.sets.set_sizes()
+    }
+
+    /// All sets with their keys in insertion order, ordered by their first inserted key.
+    pub fn sets(&mut self) -> Vec<Vec<K>> {
+        self.sets
+            .sets()
+            .into_iter()
+            .map(|set| {
+                set.into_iter()
+                    .map(|index| self.keys[index].clone())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Coordinate, Matrix};
+
+    #[test]
+    fn test_union_find() {
+        let mut sets = UnionFind::new(6);
+        assert_eq!(sets.set_count(), 6);
+        assert!(sets.union(0, 1));
+        assert!(sets.union(2, 3));
+        assert!(sets.union(1, 3));
+        assert!(!sets.union(0, 2));
+        assert!(sets.connected(0, 3));
+        assert!(!sets.connected(0, 4));
+        assert_eq!(
+            (sets.set_count(), sets.size_of(2), sets.size_of(5)),
+            (3, 4, 1)
+        );
+        assert_eq!(sets.push(), 6);
+        sets.union(6, 5);
+        assert_eq!(sets.set_sizes(), [4, 2, 1]);
+        assert_eq!(sets.sets(), [vec![0, 1, 2, 3], vec![4], vec![5, 6]]);
+    }
+
+    #[test]
+    fn test_long_chain() {
+        let mut sets = UnionFind::new(100_000);
+        for element in 1..sets.len() {
+            sets.union(element - 1, element);
+        }
+        assert_eq!(sets.set_count(), 1);
+        assert_eq!(sets.size_of(0), 100_000);
+    }
+
+    #[test]
+    fn test_keyed_regions() {
+        let rows = ["AAAA", "BBCD", "BBCC", "EEEC"];
+        let mut garden = Matrix::new(rows.iter().map(|row| row.chars().collect()).collect());
+        let (height, width) = garden.size();
+        let mut regions: KeyedUnionFind<Coordinate> = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .collect();
+        for row in 0..height {
+            for col in 0..width {
+                let plant = *garden.get((row, col)).unwrap();
+                for neighbour in [(row + 1, col), (row, col + 1)] {
+                    if garden.get(neighbour).is_some_and(|other| *other == plant) {
+                        regions.union((row, col), neighbour);
+                    }
+                }
+            }
+        }
+        assert_eq!(regions.set_count(), 5);
+        assert_eq!(regions.set_sizes(), [4, 4, 4, 3, 1]);
+        assert!(regions.connected(&(1, 2), &(3, 3)));
+        assert!(!regions.connected(&(1, 2), &(1, 3)));
+        assert_eq!(regions.size_of(&(0, 0)), 4);
+        assert_eq!(regions.size_of(&(9, 9)), 0);
+        assert_eq!(regions.find(&(9, 9)), None);
+        assert_eq!(regions.sets()[2], [(1, 2), (2, 2), (2, 3), (3, 3)]);
+    }
+}
@@ -0,0 +1,425 @@
+//! Register machine for assembly-style puzzles. Solutions only describe their opcodes in a table.
+
+use std::{collections::HashMap, fmt::Display};
+
+use crate::InputError;
+
+pub const MAX_OPERANDS: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(usize),
+    Value(i64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Instruction {
+    /// Index of the opcode in the machine's opcode table.
+    pub opcode: usize,
+    operands: [Operand; MAX_OPERANDS],
+    arity: usize,
+}
+
+impl Instruction {
+    pub fn operands(&self) -> &[Operand] {
+        &self.operands[..self.arity]
+    }
+}
+
+/// What the machine does after an instruction has been executed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flow {
+    Next,
+    /// Moves the program counter relative to the current instruction.
+    Jump(i64),
+    Halt,
+}
+
+pub type Handler = fn(&mut Machine, &[Operand]) -> Flow;
+
+#[derive(Clone, Copy)]
+pub struct Opcode {
+    pub name: &'static str,
+    pub arity: usize,
+    pub execute: Handler,
+}
+
+impl Opcode {
+    pub const fn new(name: &'static str, arity: usize, execute: Handler) -> Self {
+        assert!(arity <= MAX_OPERANDS);
+        Self {
+            name,
+            arity,
+            execute,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    /// The program counter left the program or an instruction halted the machine.
+    Halted,
+    /// The condition passed to `run_until` became true.
+    Stopped,
+    /// The machine reached a state it has been in before and would run forever.
+    Looped { first_seen: u64 },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceEntry {
+    pub step: u64,
+    pub pc: i64,
+    pub instruction: Instruction,
+    /// Register values after the instruction was executed.
+    pub registers: Vec<i64>,
+}
+
+/// Program counter, register values and program version of a machine.
+type State = (i64, Vec<i64>, u64);
+
+pub struct Machine {
+    opcodes: &'static [Opcode],
+    program: Vec<Instruction>,
+    register_names: Vec<String>,
+    registers: Vec<i64>,
+    pc: i64,
+    steps: u64,
+    program_version: u64,
+    trace: Option<Vec<TraceEntry>>,
+    seen_states: Option<HashMap<State, u64>>,
+}
+
+impl Machine {
+    /// Reads one instruction per line, e.g. `cpy 41 a` or `jio a, +22`. Operands that are no numbers name registers,
+    /// which are created in the order of their first appearance.
+    pub fn parse(source: &str, opcodes: &'static [Opcode]) -> Result<Self, InputError> {
+        let mut register_names: Vec<String> = Vec::new();
+        let mut program = Vec::new();
+        for (index, line) in source.lines().enumerate() {
+            let error = |message: String| InputError::new(index + 1, line, 0, message);
+            let mut tokens = line
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|token| !token.is_empty());
+            let Some(name) = tokens.next() else {
+                continue;
+            };
+            let opcode = opcodes
+                .iter()
+                .position(|opcode| opcode.name == name)
+                .ok_or_else(|| error(format!("Unknown opcode '{}'", name)))?;
+            let mut operands = [Operand::Value(0); MAX_OPERANDS];
+            let mut arity = 0;
+            for token in tokens {
+                if arity == opcodes[opcode].arity {
+                    return Err(error(format!("Too many operands for '{}'", name)));
+                }
+                operands[arity] = match token.parse() {
+                    Ok(value) => Operand::Value(value),
+                    Err(_) => {
+                        let register = match register_names.iter().position(|r| r == token) {
+                            Some(register) => register,
+                            None => {
+                                register_names.push(token.to_string());
+                                register_names.len() - 1
+                            }
+                        };
+                        Operand::Register(register)
+                    }
+                };
+                arity += 1;
+            }
+            if arity < opcodes[opcode].arity {
+                return Err(error(format!("Too few operands for '{}'", name)));
+            }
+            program.push(Instruction {
+                opcode,
+                operands,
+                arity,
+            });
+        }
+        Ok(Self {
+            opcodes,
+            program,
+            registers: vec![0; register_names.len()],
+            register_names,
+            pc: 0,
+            steps: 0,
+            program_version: 0,
+            trace: None,
+            seen_states: None,
+        })
+    }
+
+    /// Records every executed instruction, see `trace`.
+    pub fn with_tracing(mut self) -> Self {
+        self.trace = Some(Vec::new());
+        self
+    }
+
+    /// Remembers every state to stop with `Outcome::Looped` once one repeats.
+    pub fn with_loop_detection(mut self) -> Self {
+        self.seen_states = Some(HashMap::new());
+        self
+    }
+
+    pub fn trace(&self) -> &[TraceEntry] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    pub fn pc(&self) -> i64 {
+        self.pc
+    }
+
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn registers(&self) -> &[i64] {
+        &self.registers
+    }
+
+    /// Value of the named register. Registers that do not occur in the program read as zero.
+    pub fn register(&self, name: &str) -> i64 {
+        self.register_index(name)
+            .map_or(0, |index| self.registers[index])
+    }
+
+    pub fn set_register(&mut self, name: &str, value: i64) {
+        if let Some(index) = self.register_index(name) {
+            self.registers[index] = value;
+        }
+    }
+
+    fn register_index(&self, name: &str) -> Option<usize> {
+        self.register_names.iter().position(|r| r == name)
+    }
+
+    pub fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.registers[register],
+            Operand::Value(value) => value,
+        }
+    }
+
+    /// Writes to a register operand. Writes to immediate values are ignored.
+    pub fn set(&mut self, operand: Operand, value: i64) {
+        if let Operand::Register(register) = operand {
+            self.registers[register] = value;
+        }
+    }
+
+    pub fn opcode_index(&self, name: &str) -> Option<usize> {
+        self.opcodes.iter().position(|opcode| opcode.name == name)
+    }
+
+    pub fn program(&self) -> &[Instruction] {
+        &self.program
+    }
+
+    /// Instruction at an absolute position for self-modifying programs. `None` if it lies outside the program.
+    pub fn instruction_mut(&mut self, position: i64) -> Option<&mut Instruction> {
+        let instruction = usize::try_from(position)
+            .ok()
+            .and_then(|position| self.program.get_mut(position))?;
+        self.program_version += 1;
+        Some(instruction)
+    }
+
+    /// Executes a single instruction. Returns `false` if the machine is halted.
+    pub fn step(&mut self) -> bool {
+        let Some(instruction) = usize::try_from(self.pc)
+            .ok()
+            .and_then(|pc| self.program.get(pc))
+            .copied()
+        else {
+            return false;
+        };
+        let pc = self.pc;
+        let flow = (self.opcodes[instruction.opcode].execute)(self, instruction.operands());
+        self.steps += 1;
+        if let Some(trace) = &mut self.trace {
+            trace.push(TraceEntry {
+                step: self.steps,
+                pc,
+                instruction,
+                registers: self.registers.clone(),
+            });
+        }
+        match flow {
+            Flow::Next => self.pc += 1,
+            Flow::Jump(offset) => self.pc += offset,
+            Flow::Halt => {
+                self.pc = -1;
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn run(&mut self) -> Outcome {
+        self.run_until(|_| false)
+    }
+
+    /// Runs until the machine halts, loops or `condition` holds after an instruction.
+    pub fn run_until(&mut self, mut condition: impl FnMut(&Machine) -> bool) -> Outcome {
+        loop {
+            if let Some(seen_states) = &mut self.seen_states {
+                let state = (self.pc, self.registers.clone(), self.program_version);
+                if let Some(first_seen) = seen_states.insert(state, self.steps) {
+                    return Outcome::Looped { first_seen };
+                }
+            }
+            if !self.step() {
+                return Outcome::Halted;
+            }
+            if condition(self) {
+                return Outcome::Stopped;
+            }
+        }
+    }
+
+    /// Renders an instruction with the register names of this machine, e.g. `cpy 41 a`.
+    pub fn format(&self, instruction: &Instruction) -> String {
+        let operands = instruction.operands().iter().map(|operand| match operand {
+            Operand::Register(register) => self.register_names[*register].clone(),
+            Operand::Value(value) => value.to_string(),
+        });
+        std::iter::once(self.opcodes[instruction.opcode].name.to_string())
+            .chain(operands)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl Display for Machine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "pc={}", self.pc)?;
+        for (name, value) in self.register_names.iter().zip(&self.registers) {
+            write!(f, " {}={}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn jump_if(condition: bool, offset: i64) -> Flow {
+        if condition {
+            Flow::Jump(offset)
+        } else {
+            Flow::Next
+        }
+    }
+
+    const ASSEMBUNNY: &[Opcode] = &[
+        Opcode::new("cpy", 2, |m, o| {
+            m.set(o[1], m.value(o[0]));
+            Flow::Next
+        }),
+        Opcode::new("inc", 1, |m, o| {
+            m.set(o[0], m.value(o[0]) + 1);
+            Flow::Next
+        }),
+        Opcode::new("dec", 1, |m, o| {
+            m.set(o[0], m.value(o[0]) - 1);
+            Flow::Next
+        }),
+        Opcode::new("jnz", 2, |m, o| jump_if(m.value(o[0]) != 0, m.value(o[1]))),
+        Opcode::new("tgl", 1, |m, o| {
+            let toggled = |m: &Machine, name: &str, arity: usize| {
+                let name = match (name, arity) {
+                    ("inc", _) => "dec",
+                    (_, 1) => "inc",
+                    ("jnz", _) => "cpy",
+                    _ => "jnz",
+                };
+                m.opcode_index(name).unwrap()
+            };
+            let position = m.pc() + m.value(o[0]);
+            if let Some(instruction) = m.program().get(position as usize).copied() {
+                let name = m.opcodes[instruction.opcode].name;
+                let opcode = toggled(m, name, instruction.arity);
+                m.instruction_mut(position).unwrap().opcode = opcode;
+            }
+            Flow::Next
+        }),
+    ];
+
+    #[test]
+    fn test_run() {
+        let mut machine =
+            Machine::parse("cpy 41 a\ninc a\ninc a\ndec a\njnz a 2\ndec a", ASSEMBUNNY).unwrap();
+        assert_eq!(machine.run(), Outcome::Halted);
+        assert_eq!(machine.register("a"), 42);
+        assert_eq!(machine.steps(), 5);
+    }
+
+    #[test]
+    fn test_self_modification() {
+        let mut machine = Machine::parse(
+            "cpy 2 a\ntgl a\ntgl a\ntgl a\ncpy 1 a\ndec a\ndec a",
+            ASSEMBUNNY,
+        )
+        .unwrap()
+        .with_loop_detection();
+        assert_eq!(machine.run(), Outcome::Halted);
+        assert_eq!(machine.register("a"), 3);
+    }
+
+    #[test]
+    fn test_run_until_and_trace() {
+        const OPCODES: &[Opcode] = &[
+            Opcode::new("inc", 1, |m, o| {
+                m.set(o[0], m.value(o[0]) + 1);
+                Flow::Next
+            }),
+            Opcode::new("tpl", 1, |m, o| {
+                m.set(o[0], m.value(o[0]) * 3);
+                Flow::Next
+            }),
+            Opcode::new("jio", 2, |m, o| jump_if(m.value(o[0]) == 1, m.value(o[1]))),
+        ];
+        let mut machine = Machine::parse("inc a\njio a, +2\ntpl a\ninc a", OPCODES)
+            .unwrap()
+            .with_tracing();
+        assert_eq!(
+            machine.run_until(|m| m.register("a") == 1),
+            Outcome::Stopped
+        );
+        assert_eq!(machine.run(), Outcome::Halted);
+        assert_eq!(machine.register("a"), 2);
+        let trace: Vec<_> = machine
+            .trace()
+            .iter()
+            .map(|entry| (entry.pc, machine.format(&entry.instruction)))
+            .collect();
+        assert_eq!(
+            trace,
+            [
+                (0, "inc a".to_string()),
+                (1, "jio a 2".to_string()),
+                (3, "inc a".to_string())
+            ]
+        );
+        assert_eq!(machine.to_string(), "pc=4 a=2");
+    }
+
+    #[test]
+    fn test_loop_detection() {
+        let mut machine = Machine::parse("inc a\ncpy 0 a\njnz 1 -1", ASSEMBUNNY)
+            .unwrap()
+            .with_loop_detection();
+        assert_eq!(machine.run(), Outcome::Looped { first_seen: 2 });
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = Machine::parse("inc a\nmul a b", ASSEMBUNNY).err().unwrap();
+        assert_eq!(error.line_number, 2);
+        assert_eq!(error.message, "Unknown opcode 'mul'");
+        let error = Machine::parse("cpy a", ASSEMBUNNY).err().unwrap();
+        assert_eq!(error.message, "Too few operands for 'cpy'");
+    }
+}